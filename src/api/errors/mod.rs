@@ -0,0 +1,189 @@
+mod services;
+mod validation;
+
+use std::{borrow::Cow, fmt};
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use utoipa::{
+    openapi::{schema::Type, ObjectBuilder, RefOr, Schema},
+    PartialSchema, ToSchema,
+};
+
+use crate::{
+    api::{JsonMessage, JsonMessageWithContext},
+    services::order::order_status::OrderStatus,
+};
+
+#[derive(Serialize)]
+struct InvalidQuantity {
+    ids: Vec<u32>,
+    min: u32,
+    max: u32,
+}
+
+#[derive(Serialize)]
+struct StatusTransition {
+    from: OrderStatus,
+    to: OrderStatus,
+    allowed: &'static [OrderStatus],
+}
+
+/// Error answered by every handler. Serialized as `{"message": code}`,
+/// with `context` next to it when there are details.
+///
+/// Codes are part of the API contract, clients match on them,
+/// so existing ones must not be renamed.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    context: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str) -> Self {
+        Self {
+            status,
+            code,
+            context: None,
+        }
+    }
+
+    pub fn with_context<T>(mut self, context: T) -> Self
+    where
+        T: Serialize,
+    {
+        match serde_json::to_value(context) {
+            Ok(context) => self.context = Some(context),
+            Err(err) => log::error!("Context of {} dropped: {:?}", self.code, err),
+        }
+
+        self
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn invalid_data() -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_data")
+    }
+
+    pub fn internal_error() -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found")
+    }
+
+    pub fn not_found_ids(ids: Vec<u32>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found_ids").with_context(ids)
+    }
+
+    pub fn duplicated_ids(ids: Vec<u32>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "duplicated_ids").with_context(ids)
+    }
+
+    pub fn invalid_quantity(ids: Vec<u32>, max: u32) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_quantity").with_context(InvalidQuantity {
+            ids,
+            min: 1,
+            max,
+        })
+    }
+
+    pub fn out_of_stock(ids: Vec<u32>) -> Self {
+        Self::new(StatusCode::CONFLICT, "out_of_stock").with_context(ids)
+    }
+
+    pub fn variant_required(ids: Vec<u32>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "variant_required").with_context(ids)
+    }
+
+    pub fn conflict() -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict")
+    }
+
+    pub fn already_exists() -> Self {
+        Self::new(StatusCode::CONFLICT, "already_exists")
+    }
+
+    pub fn too_many_requests() -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, "too_many_requests")
+    }
+
+    pub fn verification_failed() -> Self {
+        Self::new(StatusCode::FORBIDDEN, "verification_failed")
+    }
+
+    pub fn need_authorization() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "need_authorization")
+    }
+
+    pub fn invalid_token() -> Self {
+        Self::new(StatusCode::FORBIDDEN, "invalid_token")
+    }
+
+    pub fn invalid_status_transition(from: OrderStatus, to: OrderStatus) -> Self {
+        Self::new(StatusCode::CONFLICT, "invalid_status_transition").with_context(
+            StatusTransition {
+                from,
+                to,
+                allowed: from.next_statuses(),
+            },
+        )
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status);
+
+        match &self.context {
+            Some(context) => response.json(JsonMessageWithContext {
+                message: self.code,
+                context,
+            }),
+            None => response.json(JsonMessage { message: self.code }),
+        }
+    }
+}
+
+/// Shape of the error body in the OpenAPI document.
+impl PartialSchema for ApiError {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property(
+                "message",
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .description(Some("Error code, e.g. `not_found`")),
+            )
+            .required("message")
+            .property(
+                "context",
+                ObjectBuilder::new().description(Some("Details, depend on the code")),
+            )
+            .into()
+    }
+}
+
+impl ToSchema for ApiError {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("ApiError")
+    }
+}
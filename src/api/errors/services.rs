@@ -0,0 +1,250 @@
+use actix_web::http::StatusCode;
+use validator::ValidationErrors;
+
+use crate::services::{
+    antispam::{AntiSpamErr, SpamReason},
+    auth::AuthServiceError,
+    category::CategoriesServiceErr,
+    company_services::dto::{GetCreateCompanyServicesError, UpdateRemoveCompanyServiceError},
+    field::dto::{FieldCreateError, FieldGetRemoveError, FieldUpdateError},
+    files::FilesServiceErr,
+    idempotency::IdempotencyErr,
+    invoice::InvoiceErr,
+    order::{
+        export::OrderExportErr, OrderGetError, OrderInsertionErr, OrderService,
+        OrderStatusUpdateErr,
+    },
+    phone::PhoneErr,
+    product::ProductServiceErr,
+    webhook::WebhookServiceErr,
+};
+
+use super::ApiError;
+
+impl From<ValidationErrors> for ApiError {
+    fn from(value: ValidationErrors) -> Self {
+        Self::validation(&value)
+    }
+}
+
+impl From<ProductServiceErr> for ApiError {
+    fn from(value: ProductServiceErr) -> Self {
+        match value {
+            ProductServiceErr::NotFound => Self::not_found(),
+            ProductServiceErr::InvalidFieldFilter(id) => Self::invalid_field(
+                &format!("field.{id}"),
+                "invalid_field_filter",
+                "Unknown field or a value it can't be compared with",
+            ),
+            ProductServiceErr::InvalidParentId => {
+                Self::new(StatusCode::BAD_REQUEST, "invalid_parent_id")
+            }
+            ProductServiceErr::FilesNotFound(files) => {
+                Self::new(StatusCode::BAD_REQUEST, "file_not_found").with_context(files)
+            }
+            ProductServiceErr::InvalidImageOrder => {
+                Self::new(StatusCode::BAD_REQUEST, "invalid_image_order")
+            }
+            ProductServiceErr::OutOfStock(id) => Self::out_of_stock(vec![id]),
+            ProductServiceErr::HasVariants(ids) => {
+                Self::new(StatusCode::CONFLICT, "product_has_variants").with_context(ids)
+            }
+            ProductServiceErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<CategoriesServiceErr> for ApiError {
+    fn from(value: CategoriesServiceErr) -> Self {
+        match value {
+            CategoriesServiceErr::NotFound => Self::not_found(),
+            CategoriesServiceErr::AlreadyExists => Self::already_exists(),
+            CategoriesServiceErr::InvalidParentId => {
+                Self::new(StatusCode::BAD_REQUEST, "invalid_parent_id")
+            }
+            CategoriesServiceErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<FilesServiceErr> for ApiError {
+    fn from(value: FilesServiceErr) -> Self {
+        match value {
+            FilesServiceErr::NotFound => Self::not_found(),
+            FilesServiceErr::NoFilesToUpload => {
+                Self::new(StatusCode::BAD_REQUEST, "no_files_to_upload")
+            }
+            FilesServiceErr::ForbiddenFileType => {
+                Self::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, "forbidden_file_type")
+            }
+            FilesServiceErr::MaxFileSizeExceed => {
+                Self::new(StatusCode::PAYLOAD_TOO_LARGE, "max_file_size_exceeded")
+            }
+            FilesServiceErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<AuthServiceError> for ApiError {
+    fn from(value: AuthServiceError) -> Self {
+        match value {
+            // Which one it was isn't told, so that usernames can't be probed.
+            AuthServiceError::UserNotFound | AuthServiceError::InvalidPassword => {
+                Self::invalid_data()
+            }
+            AuthServiceError::InvalidToken => Self::invalid_token(),
+            AuthServiceError::TokenExpired => Self::new(StatusCode::UNAUTHORIZED, "token_expired"),
+            AuthServiceError::AccessTokenGeneration
+            | AuthServiceError::RefreshTokenGeneration
+            | AuthServiceError::PasswordVerify
+            | AuthServiceError::InternalError => Self::internal_error(),
+        }
+    }
+}
+
+impl From<FieldCreateError> for ApiError {
+    fn from(value: FieldCreateError) -> Self {
+        match value {
+            FieldCreateError::AlreadyExists => Self::already_exists(),
+            FieldCreateError::Unknown => Self::internal_error(),
+        }
+    }
+}
+
+impl From<FieldGetRemoveError> for ApiError {
+    fn from(value: FieldGetRemoveError) -> Self {
+        match value {
+            FieldGetRemoveError::NotFound => Self::not_found(),
+            FieldGetRemoveError::Unknown => Self::internal_error(),
+        }
+    }
+}
+
+impl From<FieldUpdateError> for ApiError {
+    fn from(value: FieldUpdateError) -> Self {
+        match value {
+            FieldUpdateError::NotFound => Self::not_found(),
+            FieldUpdateError::Unknown => Self::internal_error(),
+        }
+    }
+}
+
+impl From<GetCreateCompanyServicesError> for ApiError {
+    fn from(value: GetCreateCompanyServicesError) -> Self {
+        match value {
+            GetCreateCompanyServicesError::InternalError => Self::internal_error(),
+        }
+    }
+}
+
+impl From<UpdateRemoveCompanyServiceError> for ApiError {
+    fn from(value: UpdateRemoveCompanyServiceError) -> Self {
+        match value {
+            UpdateRemoveCompanyServiceError::NotFound => Self::not_found(),
+            UpdateRemoveCompanyServiceError::InternalError => Self::internal_error(),
+        }
+    }
+}
+
+impl From<OrderInsertionErr> for ApiError {
+    fn from(value: OrderInsertionErr) -> Self {
+        match value {
+            OrderInsertionErr::ProductNotFound(ids) => Self::not_found_ids(ids),
+            OrderInsertionErr::DuplicatedProducts(ids) => Self::duplicated_ids(ids),
+            OrderInsertionErr::InvalidQuantity(ids) => {
+                Self::invalid_quantity(ids, OrderService::MAX_PRODUCT_QUANTITY)
+            }
+            OrderInsertionErr::OutOfStock(ids) => Self::out_of_stock(ids),
+            OrderInsertionErr::VariantRequired(ids) => Self::variant_required(ids),
+            OrderInsertionErr::EmptyOrder => Self::new(StatusCode::BAD_REQUEST, "empty_order"),
+            OrderInsertionErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<OrderGetError> for ApiError {
+    fn from(value: OrderGetError) -> Self {
+        match value {
+            OrderGetError::NotFound => Self::not_found(),
+            OrderGetError::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<OrderStatusUpdateErr> for ApiError {
+    fn from(value: OrderStatusUpdateErr) -> Self {
+        match value {
+            OrderStatusUpdateErr::NotFound => Self::not_found(),
+            OrderStatusUpdateErr::InvalidTransition(from, to) => {
+                Self::invalid_status_transition(from, to)
+            }
+            OrderStatusUpdateErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<OrderExportErr> for ApiError {
+    fn from(value: OrderExportErr) -> Self {
+        match value {
+            OrderExportErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<InvoiceErr> for ApiError {
+    fn from(value: InvoiceErr) -> Self {
+        match value {
+            InvoiceErr::NotFound => Self::not_found(),
+            InvoiceErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<WebhookServiceErr> for ApiError {
+    fn from(value: WebhookServiceErr) -> Self {
+        match value {
+            WebhookServiceErr::NotFound => Self::not_found(),
+            WebhookServiceErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<IdempotencyErr> for ApiError {
+    fn from(value: IdempotencyErr) -> Self {
+        match value {
+            IdempotencyErr::InvalidKey => {
+                Self::new(StatusCode::BAD_REQUEST, "invalid_idempotency_key")
+            }
+            IdempotencyErr::Internal => Self::internal_error(),
+        }
+    }
+}
+
+impl From<PhoneErr> for ApiError {
+    fn from(value: PhoneErr) -> Self {
+        match value {
+            PhoneErr::Invalid => {
+                Self::invalid_field("phone", "invalid_phone", "Phone number is not valid")
+            }
+        }
+    }
+}
+
+impl From<SpamReason> for ApiError {
+    fn from(value: SpamReason) -> Self {
+        match value {
+            // Bots aren't told that the honeypot gave them away.
+            SpamReason::Honeypot => Self::invalid_data(),
+            SpamReason::Verification => Self::verification_failed(),
+            SpamReason::IpRateLimit | SpamReason::PhoneRateLimit => Self::too_many_requests(),
+        }
+    }
+}
+
+impl From<AntiSpamErr> for ApiError {
+    fn from(value: AntiSpamErr) -> Self {
+        match value {
+            AntiSpamErr::Internal => Self::internal_error(),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use actix_web::{error::JsonPayloadError, http::StatusCode};
+use serde::Serialize;
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use super::ApiError;
+
+/// Field name, or `products[0].quantity` for nested ones, to its errors.
+/// The whole body is reported under `body`.
+type FieldErrors = BTreeMap<String, Vec<FieldError>>;
+
+#[derive(Serialize)]
+struct FieldError {
+    code: Cow<'static, str>,
+    message: Cow<'static, str>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    params: BTreeMap<Cow<'static, str>, serde_json::Value>,
+}
+
+impl FieldError {
+    fn new(code: &'static str, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            params: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<&ValidationError> for FieldError {
+    fn from(value: &ValidationError) -> Self {
+        let mut params: BTreeMap<_, _> = value.params.clone().into_iter().collect();
+        // Rejected input isn't echoed back, it may be a password.
+        params.remove("value");
+
+        let message = value
+            .message
+            .clone()
+            .unwrap_or_else(|| default_message(&value.code, &params).into());
+
+        Self {
+            code: value.code.clone(),
+            message,
+            params,
+        }
+    }
+}
+
+fn default_message(code: &str, params: &BTreeMap<Cow<'static, str>, serde_json::Value>) -> String {
+    let bound = |name: &str| params.get(name).map(ToString::to_string);
+
+    match (code, bound("min"), bound("max")) {
+        ("length", Some(min), None) if min == "1" => "Must not be empty".into(),
+        ("length", Some(min), Some(max)) => format!("Must be {min} to {max} characters long"),
+        ("length", Some(min), None) => format!("Must be at least {min} characters long"),
+        ("length", None, Some(max)) => format!("Must be at most {max} characters long"),
+        ("range", Some(min), Some(max)) => format!("Must be between {min} and {max}"),
+        ("range", Some(min), None) => format!("Must be at least {min}"),
+        ("range", None, Some(max)) => format!("Must be at most {max}"),
+        ("email", _, _) => "Must be a valid email".into(),
+        ("url", _, _) => "Must be a valid URL".into(),
+        _ => "Is not valid".into(),
+    }
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, result: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        // Raw identifiers like `r#type` are reported as they're named in JSON.
+        let field = field.trim_start_matches("r#");
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                result
+                    .entry(path)
+                    .or_default()
+                    .extend(errors.iter().map(FieldError::from));
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(errors, &path, result),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_field_errors(errors, &format!("{path}[{index}]"), result);
+                }
+            }
+        }
+    }
+}
+
+impl ApiError {
+    /// `invalid_data` with the reason a single field was rejected,
+    /// in the context keyed by the field name.
+    pub fn invalid_field(field: &str, code: &'static str, message: &'static str) -> Self {
+        Self::invalid_fields(FieldErrors::from([(
+            field.to_owned(),
+            vec![FieldError::new(code, message)],
+        )]))
+    }
+
+    /// `invalid_data` listing every field that failed `Validate`.
+    pub fn validation(errors: &ValidationErrors) -> Self {
+        let mut fields = FieldErrors::new();
+
+        collect_field_errors(errors, "", &mut fields);
+
+        Self::invalid_fields(fields)
+    }
+
+    /// Error for a JSON body that couldn't be read or deserialized.
+    pub fn json_payload(err: &JsonPayloadError) -> Self {
+        let error = match err {
+            JsonPayloadError::Deserialize(err) if err.is_data() => {
+                let message = err.to_string();
+
+                // serde_json only names the field when it's missing.
+                if let Some(field) = message
+                    .strip_prefix("missing field `")
+                    .and_then(|rest| rest.split('`').next())
+                {
+                    return Self::invalid_field(field, "required", "Is required");
+                }
+
+                FieldError::new("invalid_type", message)
+            }
+            JsonPayloadError::Deserialize(err) => FieldError::new("invalid_json", err.to_string()),
+            JsonPayloadError::ContentType => {
+                FieldError::new("content_type", "Must be application/json")
+            }
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                FieldError::new("too_large", "Body is too large")
+            }
+            _ => return Self::invalid_data(),
+        };
+
+        Self::invalid_fields(FieldErrors::from([("body".to_owned(), vec![error])]))
+    }
+
+    fn invalid_fields(fields: FieldErrors) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "invalid_data").with_context(fields)
+    }
+}
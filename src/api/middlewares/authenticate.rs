@@ -5,10 +5,13 @@ use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
     http::header,
     web::Data,
-    HttpMessage, HttpRequest,
+    HttpMessage, HttpRequest, ResponseError,
 };
 
-use crate::services::auth::{AuthService, SecretsProvider};
+use crate::{
+    api::errors::ApiError,
+    services::auth::{AuthService, SecretsProvider},
+};
 use futures_util::future::LocalBoxFuture;
 
 pub struct JwtAuthService<S, T>
@@ -22,10 +25,8 @@ where
 macro_rules! need_authorization {
     ($req:ident) => {
         let res = $req.into_response(
-            actix_web::HttpResponse::Unauthorized()
-                .json(crate::api::JsonMessage {
-                    message: "need_authorization",
-                })
+            ApiError::need_authorization()
+                .error_response()
                 .map_into_boxed_body(),
         );
         return Box::pin(async move {
@@ -50,7 +51,7 @@ pub fn extract_auth_token(req: &HttpRequest) -> Option<&str> {
 
     let mut auth_value = auth_value.unwrap().split(' ');
     let auth_type = auth_value.next();
-    let token = auth_value.last();
+    let token = auth_value.next_back();
 
     if auth_type.is_none() || token.is_none() {
         return None;
@@ -91,10 +92,8 @@ where
 
         if data.is_err() {
             let res = req.into_response(
-                actix_web::HttpResponse::Forbidden()
-                    .json(crate::api::JsonMessage {
-                        message: "invalid_token",
-                    })
+                ApiError::invalid_token()
+                    .error_response()
                     .map_into_boxed_body(),
             );
             return Box::pin(async move {
@@ -6,6 +6,11 @@ pub use v1::FieldInProductDto;
 
 use actix_web::web::{self, Data};
 use serde::Serialize;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::config::Config;
 
@@ -20,6 +25,38 @@ pub struct JsonMessageWithContext<'a, T: Serialize> {
     pub context: T,
 }
 
+#[derive(OpenApi)]
+#[openapi(
+    nest((path = "/api/v1", api = v1::V1Api)),
+    modifiers(&BearerAuth),
+)]
+struct ApiDoc;
+
+/// Access token checked by `JwtAuth`, referenced by the paths as `bearer_auth`.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Swagger UI at `/api/docs/` with the document at `/api/docs/openapi.json`.
+/// Registered on the app, ahead of the `/api` scope which would shadow it.
+pub(super) fn docs() -> SwaggerUi {
+    SwaggerUi::new("/api/docs/{_:.*}").url("/api/docs/openapi.json", ApiDoc::openapi())
+}
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(web::scope("/v1").configure(v1::configure(config.clone())));
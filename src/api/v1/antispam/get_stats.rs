@@ -0,0 +1,23 @@
+use actix_web::{web::Data, HttpResponse};
+
+use crate::{
+    api::errors::ApiError,
+    cache::Cache,
+    services::antispam::{AntiSpamService, SpamStat},
+};
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "antispam",
+    responses((status = 200, body = Vec<SpamStat>)),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_stats(
+    antispam_service: Data<AntiSpamService>,
+    cache: Data<Cache>,
+) -> Result<HttpResponse, ApiError> {
+    let stats = antispam_service.stats(&cache)?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
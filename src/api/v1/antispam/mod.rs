@@ -0,0 +1,20 @@
+mod get_stats;
+
+use actix_web::web::{self, Data};
+use utoipa::OpenApi;
+
+use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
+
+#[derive(OpenApi)]
+#[openapi(paths(get_stats::get_stats))]
+pub(super) struct AntiSpamApi;
+
+pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.service(
+            web::resource("stats")
+                .wrap(JwtAuth::new(config.clone()))
+                .get(get_stats::get_stats),
+        );
+    }
+}
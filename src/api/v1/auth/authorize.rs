@@ -5,7 +5,7 @@ use actix_web::{
     },
     post,
     web::{Data, Json},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
@@ -16,37 +16,40 @@ use crate::{
     },
     cache::Cache,
     config::Config,
-    services::auth::{AuthService, AuthServiceError},
+    services::auth::AuthService,
 };
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "auth",
+    request_body = AuthorizationDto,
+    responses(
+        (
+            status = 200,
+            body = AuthDataResult,
+            description = "Also sets the `refresh_token` cookie",
+        ),
+        (status = 400, body = ApiError),
+        (status = 401, body = ApiError),
+    ),
+)]
 #[post("")]
 pub(super) async fn authorize(
     json: Json<AuthorizationDto>,
     cache: Data<Cache>,
     auth_service: Data<AuthService>,
     config: Data<Config>,
-) -> impl Responder {
-    if json.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    json.validate()?;
 
-    let db_result = auth_service
+    let tokens = auth_service
         .authorize_user(&json.0.username, &json.0.password, config.as_ref())
-        .await;
-
-    if let Err(db_err) = db_result {
-        match db_err {
-            AuthServiceError::UserNotFound => return ApiError::invalid_data(),
-            AuthServiceError::InvalidPassword => return ApiError::invalid_data(),
-            _ => return ApiError::internal_error(),
-        }
-    }
-
-    let tokens = db_result.unwrap();
+        .await?;
     let _ = cache.add_pair(&tokens.1, &tokens.0, tokens.3);
     let expires_time = OffsetDateTime::from_unix_timestamp(tokens.3 as i64 * 1000);
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok()
         .cookie(
             Cookie::build("refresh_token", tokens.1)
                 .secure(true)
@@ -58,5 +61,5 @@ pub(super) async fn authorize(
         .json(AuthDataResult {
             access_token: tokens.0,
             expires: tokens.2,
-        })
+        }))
 }
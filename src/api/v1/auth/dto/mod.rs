@@ -1,7 +1,8 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct AuthorizationDto {
     #[validate(length(min = 3, max = 255))]
     pub username: String,
@@ -10,6 +10,12 @@ use actix_web::{
 
 use crate::{api::JsonMessage, cache::Cache};
 
+#[utoipa::path(
+    post,
+    path = "/logout",
+    tag = "auth",
+    responses((status = 200, description = "Removes the `refresh_token` cookie")),
+)]
 #[post("logout")]
 pub(super) async fn logout(req: HttpRequest, cache: Data<Cache>) -> impl Responder {
     let refresh_token = req.cookie("refresh_token");
@@ -5,13 +5,18 @@ mod refresh_tokens;
 
 use actix_web::web;
 use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct AuthDataResult {
     access_token: String,
     expires: usize,
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(authorize::authorize, refresh_tokens::refresh_tokens, logout::logout))]
+pub(super) struct AuthApi;
+
 pub(super) fn configure() -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(authorize::authorize)
@@ -3,92 +3,81 @@ use actix_web::{
         time::{ext::NumericalDuration, OffsetDateTime},
         Cookie,
     },
+    http::StatusCode,
     post,
     web::Data,
-    HttpRequest, HttpResponse, Responder,
+    HttpRequest, HttpResponse,
 };
 
 use crate::{
-    api::{errors::ApiError, v1::auth::AuthDataResult, JsonMessage},
+    api::{errors::ApiError, v1::auth::AuthDataResult},
     cache::Cache,
     config::Config,
     services::auth::{AuthService, AuthServiceError},
 };
 
+fn refresh_token_not_found() -> ApiError {
+    ApiError::new(StatusCode::UNAUTHORIZED, "refresh_token_not_found")
+}
+
+#[utoipa::path(
+    post,
+    path = "/refresh-tokens",
+    tag = "auth",
+    responses(
+        (
+            status = 200,
+            body = AuthDataResult,
+            description = "Also rotates the `refresh_token` cookie",
+        ),
+        (status = 401, body = ApiError),
+    ),
+)]
 #[post("refresh-tokens")]
 pub(super) async fn refresh_tokens(
     req: HttpRequest,
     cache: Data<Cache>,
     config: Data<Config>,
     auth_service: Data<AuthService>,
-) -> impl Responder {
-    let refresh_token = req.cookie("refresh_token");
-    let refresh_token_not_found = HttpResponse::Unauthorized().json(JsonMessage {
-        message: "refresh_token_not_found",
-    });
-    let internal_error = ApiError::internal_error();
-
-    if refresh_token.is_none() {
-        return refresh_token_not_found;
-    }
-
-    let refresh_token = refresh_token.unwrap();
+) -> Result<HttpResponse, ApiError> {
+    let refresh_token = req
+        .cookie("refresh_token")
+        .ok_or_else(refresh_token_not_found)?;
     let refresh_token = refresh_token.value();
-    let access_token = cache.get_pair(refresh_token);
-
-    if access_token.is_err() {
-        return internal_error;
-    }
-
-    let access_token = access_token.unwrap();
-
-    if access_token.is_none() {
-        return refresh_token_not_found;
-    }
-
-    let access_token = access_token.unwrap();
 
     if refresh_token.is_empty() {
-        return refresh_token_not_found;
+        return Err(refresh_token_not_found());
     }
 
-    let user_data = AuthService::decrypt_token(&access_token, config.as_ref());
+    let access_token = cache
+        .get_pair(refresh_token)
+        .map_err(|_| ApiError::internal_error())?
+        .ok_or_else(refresh_token_not_found)?;
 
-    if let Err(err) = user_data {
-        match err {
+    let user_data =
+        AuthService::decrypt_token(&access_token, config.as_ref()).map_err(|err| match err {
             AuthServiceError::InvalidToken => {
-                return HttpResponse::BadRequest().json(JsonMessage {
-                    message: "invalid_token",
-                })
+                ApiError::new(StatusCode::BAD_REQUEST, "invalid_token")
             }
-            _ => return internal_error,
-        }
-    }
+            _ => ApiError::internal_error(),
+        })?;
 
-    let user_data = user_data.unwrap();
-    let service_result = auth_service
+    let tokens = auth_service
         .refresh_tokens(&user_data, config.as_ref())
-        .await;
-
-    if let Err(err) = service_result {
-        match err {
+        .await
+        .map_err(|err| match err {
             AuthServiceError::UserNotFound => {
-                return HttpResponse::NotFound().json(JsonMessage {
-                    message: "user_not_found",
-                })
+                ApiError::new(StatusCode::NOT_FOUND, "user_not_found")
             }
-            _ => return internal_error,
-        }
-    }
-
-    let tokens = service_result.unwrap();
+            err => err.into(),
+        })?;
 
     let _ = cache.remove(refresh_token);
     let _ = cache.add_pair(&tokens.1, &access_token, tokens.3);
 
     let expires_time = OffsetDateTime::from_unix_timestamp(tokens.3 as i64 * 1000);
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok()
         .cookie(
             Cookie::build("refresh_token", tokens.1)
                 .secure(true)
@@ -100,5 +89,5 @@ pub(super) async fn refresh_tokens(
         .json(AuthDataResult {
             access_token: tokens.0,
             expires: tokens.2 * 1000,
-        })
+        }))
 }
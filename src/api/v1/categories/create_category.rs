@@ -1,29 +1,35 @@
-use actix_web::{web::{Data, Json}, HttpResponse, Responder};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
 use validator::Validate;
 
-use crate::{api::errors::ApiError, services::category::{CategoryService, CategoriesServiceErr}};
+use crate::{
+    api::errors::ApiError,
+    services::category::{CategoryInsertion, CategoryService},
+};
 
 use super::dto::CreateCategoryDto;
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "categories",
+    request_body = CreateCategoryDto,
+    responses(
+        (status = 201, body = CategoryInsertion),
+        (status = 400, body = ApiError),
+        (status = 409, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn create_category(
     dto: Json<CreateCategoryDto>,
-    category_service: Data<CategoryService>
-) -> impl Responder {
-    if dto.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+    category_service: Data<CategoryService>,
+) -> Result<HttpResponse, ApiError> {
+    dto.validate()?;
 
-    let create_result = category_service.create(&dto.name, dto.parent_id).await;
+    let category = category_service.create(&dto.name, dto.parent_id).await?;
 
-    if create_result.is_err() {
-        return match create_result.err().unwrap() {
-            CategoriesServiceErr::AlreadyExists => ApiError::conflict(),
-            CategoriesServiceErr::InvalidParentId => ApiError::invalid_data(),
-            _ => ApiError::internal_error()
-        }
-    }
-
-    create_result
-        .map(|res| HttpResponse::Created().json(res))
-        .unwrap()
+    Ok(HttpResponse::Created().json(category))
 }
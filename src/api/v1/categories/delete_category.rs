@@ -1,30 +1,32 @@
 use actix_web::{
     web::{Data, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::{errors::ApiError, v1::categories::dto::DeleteCategoriesDto},
-    services::category::CategoryService,
+    services::category::{CategoriesIdx, CategoryService},
 };
 
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "categories",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = CategoriesIdx),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn delete_categories(
     data: Path<DeleteCategoriesDto>,
     category_service: Data<CategoryService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
-    let deletion_result = category_service
-        .delete(&[data.id])
-        .await
-        .map(|data| HttpResponse::Ok().json(data));
+    let deleted = category_service.delete(&[data.id]).await?;
 
-    if deletion_result.is_err() {
-        return ApiError::internal_error();
-    }
-
-    deletion_result.unwrap()
+    Ok(HttpResponse::Ok().json(deleted))
 }
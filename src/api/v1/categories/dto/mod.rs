@@ -1,5 +1,6 @@
 use crate::utilities::serde_utils::Patch;
 use serde::Deserialize;
+use utoipa::ToSchema;
 use validator::Validate;
 
 #[derive(Deserialize, Validate, Debug, Clone)]
@@ -7,7 +8,7 @@ pub struct DeleteCategoriesDto {
     pub id: u32,
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct CreateCategoryDto {
     #[validate(length(min = 3, max = 32))]
     pub name: String,
@@ -15,11 +16,13 @@ pub struct CreateCategoryDto {
     pub parent_id: Option<u32>,
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct UpdateCategoryDto {
     #[validate(length(min = 3, max = 32))]
     pub name: Option<String>,
 
+    /// `null` detaches the category, a missing field keeps the parent.
     #[serde(default)]
+    #[schema(value_type = Option<u32>)]
     pub parent_id: Patch<u32>,
 }
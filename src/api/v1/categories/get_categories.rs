@@ -1,57 +1,63 @@
 use actix_web::{
     get,
     web::{Data, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 
 use crate::{
     api::errors::ApiError,
-    services::category::{CategoriesServiceErr, CategoryService},
+    services::category::{
+        CategorySerializable, CategoryService, CategoryTreeSerializable,
+        CategoryWithProductsSerializable,
+    },
 };
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "categories",
+    responses((status = 200, body = Vec<CategorySerializable>)),
+)]
 #[get("")]
-pub(super) async fn get_categories(category_service: Data<CategoryService>) -> impl Responder {
-    let categories = category_service.all().await;
-
-    if categories.is_err() {
-        return ApiError::internal_error();
-    }
+pub(super) async fn get_categories(
+    category_service: Data<CategoryService>,
+) -> Result<HttpResponse, ApiError> {
+    let categories = category_service.all().await?;
 
-    categories
-        .map(|data| HttpResponse::Ok().json(data))
-        .unwrap()
+    Ok(HttpResponse::Ok().json(categories))
 }
 
+#[utoipa::path(
+    get,
+    path = "/tree",
+    tag = "categories",
+    responses((status = 200, body = Vec<CategoryTreeSerializable>)),
+)]
 #[get("/tree")]
-pub(super) async fn get_tree_categories(category_service: Data<CategoryService>) -> impl Responder {
-    let categories = category_service.all_tree().await;
-
-    if categories.is_err() {
-        return ApiError::internal_error();
-    }
+pub(super) async fn get_tree_categories(
+    category_service: Data<CategoryService>,
+) -> Result<HttpResponse, ApiError> {
+    let categories = category_service.all_tree().await?;
 
-    categories
-        .map(|data| HttpResponse::Ok().json(data))
-        .unwrap()
+    Ok(HttpResponse::Ok().json(categories))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "categories",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = CategoryWithProductsSerializable),
+        (status = 404, body = ApiError),
+    ),
+)]
 #[get("{id}")]
 pub(super) async fn get_category_with_products(
     path: Path<(u32,)>,
     category_service: Data<CategoryService>,
-) -> impl Responder {
-    let categories = category_service
-        .category_with_products(path.0)
-        .await
-        .map_err(|err| match err {
-            CategoriesServiceErr::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|value| HttpResponse::Ok().json(value));
-
-    if let Err(err) = categories {
-        return err;
-    }
+) -> Result<HttpResponse, ApiError> {
+    let category = category_service.category_with_products(path.0).await?;
 
-    categories.unwrap()
+    Ok(HttpResponse::Ok().json(category))
 }
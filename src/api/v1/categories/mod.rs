@@ -5,9 +5,21 @@ mod get_categories;
 mod patch_category;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_categories::get_categories,
+    get_categories::get_tree_categories,
+    get_categories::get_category_with_products,
+    create_category::create_category,
+    patch_category::patch_category,
+    delete_category::delete_categories,
+))]
+pub(super) struct CategoriesApi;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(get_categories::get_categories)
@@ -1,30 +1,39 @@
-use actix_web::{web::{Data, Json, Path}, HttpResponse, Responder};
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpResponse,
+};
 use validator::Validate;
 
-use crate::{api::errors::ApiError, services::category::{CategoryService, CategoriesServiceErr}};
+use crate::{
+    api::errors::ApiError,
+    services::category::{CategorySerializable, CategoryService},
+};
 
 use super::dto::UpdateCategoryDto;
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "categories",
+    params(("id" = u32, Path)),
+    request_body = UpdateCategoryDto,
+    responses(
+        (status = 200, body = CategorySerializable),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn patch_category(
     category_id: Path<u32>,
     dto: Json<UpdateCategoryDto>,
-    category_service: Data<CategoryService>
-) -> impl Responder {
-    if dto.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+    category_service: Data<CategoryService>,
+) -> Result<HttpResponse, ApiError> {
+    dto.validate()?;
 
-    let create_result = category_service.update(category_id.to_owned(), dto.name.as_deref(), dto.parent_id).await;
+    let category = category_service
+        .update(category_id.to_owned(), dto.name.as_deref(), dto.parent_id)
+        .await?;
 
-    if create_result.is_err() {
-        return match create_result.err().unwrap() {
-            CategoriesServiceErr::NotFound => ApiError::not_found(),
-            CategoriesServiceErr::InvalidParentId => ApiError::invalid_data(),
-            _ => ApiError::internal_error()
-        }
-    }
-
-    create_result
-        .map(|res| HttpResponse::Ok().json(res))
-        .unwrap()
+    Ok(HttpResponse::Ok().json(category))
 }
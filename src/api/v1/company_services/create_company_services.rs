@@ -1,30 +1,34 @@
 use actix_web::{
     web::{Data, Json},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
-use crate::{api::errors::ApiError, services::company_services::CompanyServicesService};
+use crate::{
+    api::errors::ApiError,
+    services::company_services::{dto::CompanyServiceIdSerializable, CompanyServicesService},
+};
 
 use super::dto::UpdateCreateCompanyServiceDto;
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "services",
+    request_body = UpdateCreateCompanyServiceDto,
+    responses(
+        (status = 200, body = CompanyServiceIdSerializable),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn create_service(
     data: Json<UpdateCreateCompanyServiceDto>,
     service: Data<CompanyServicesService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
-    let result = service
-        .create(&data.name, data.price)
-        .await
-        .map_err(|_| ApiError::internal_error())
-        .map(|result| HttpResponse::Ok().json(result));
+    let result = service.create(&data.name, data.price).await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(result))
 }
@@ -1,29 +1,29 @@
 use actix_web::{
     web::{Data, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 
 use crate::{
     api::errors::ApiError,
-    services::company_services::{dto::UpdateRemoveCompanyServiceError, CompanyServicesService},
+    services::company_services::{dto::CompanyServiceIdSerializable, CompanyServicesService},
 };
 
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "services",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = CompanyServiceIdSerializable),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn delete_service(
     id: Path<u32>,
     service: Data<CompanyServicesService>,
-) -> impl Responder {
-    let result = service
-        .delete(id.into_inner())
-        .await
-        .map_err(|err| match err {
-            UpdateRemoveCompanyServiceError::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+) -> Result<HttpResponse, ApiError> {
+    let result = service.delete(id.into_inner()).await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(result))
 }
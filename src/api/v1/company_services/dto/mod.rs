@@ -1,8 +1,9 @@
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use utoipa::ToSchema;
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Clone, Validate)]
+#[derive(Debug, Deserialize, Clone, Validate, ToSchema)]
 pub struct UpdateCreateCompanyServiceDto {
     #[validate(length(min = 1))]
     pub name: String,
@@ -1,18 +1,21 @@
-use actix_web::{get, web::Data, HttpResponse, Responder};
+use actix_web::{get, web::Data, HttpResponse};
 
-use crate::{api::errors::ApiError, services::company_services::CompanyServicesService};
+use crate::{
+    api::errors::ApiError,
+    services::company_services::{dto::CompanyServiceSerializable, CompanyServicesService},
+};
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "services",
+    responses((status = 200, body = Vec<CompanyServiceSerializable>)),
+)]
 #[get("")]
-pub(super) async fn get_company_services(service: Data<CompanyServicesService>) -> impl Responder {
-    let result = service
-        .get_all()
-        .await
-        .map(|result| HttpResponse::Ok().json(result))
-        .map_err(|_| ApiError::internal_error());
+pub(super) async fn get_company_services(
+    service: Data<CompanyServicesService>,
+) -> Result<HttpResponse, ApiError> {
+    let services = service.get_all().await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(services))
 }
@@ -5,9 +5,19 @@ mod get_company_services;
 mod update_company_services;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_company_services::get_company_services,
+    create_company_services::create_service,
+    update_company_services::update_service,
+    delete_company_services::delete_service,
+))]
+pub(super) struct CompanyServicesApi;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(get_company_services::get_company_services)
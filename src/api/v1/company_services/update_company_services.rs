@@ -1,37 +1,39 @@
 use actix_web::{
     web::{Data, Json, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::errors::ApiError,
-    services::company_services::{dto::UpdateRemoveCompanyServiceError, CompanyServicesService},
+    services::company_services::{dto::CompanyServiceIdSerializable, CompanyServicesService},
 };
 
 use super::dto::UpdateCreateCompanyServiceDto;
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "services",
+    params(("id" = u32, Path)),
+    request_body = UpdateCreateCompanyServiceDto,
+    responses(
+        (status = 200, body = CompanyServiceIdSerializable),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn update_service(
     id: Path<u32>,
     data: Json<UpdateCreateCompanyServiceDto>,
     service: Data<CompanyServicesService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
     let result = service
         .update(id.into_inner(), &data.name, data.price)
-        .await
-        .map(|result| HttpResponse::Ok().json(result))
-        .map_err(|err| match err {
-            UpdateRemoveCompanyServiceError::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        });
+        .await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(result))
 }
@@ -1,36 +1,35 @@
 use actix_web::{
     web::{self, Data},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::errors::ApiError,
-    services::field::{dto::FieldCreateError, FieldService},
+    services::field::{dto::FieldId, FieldService},
 };
 
 use super::dto;
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "fields",
+    request_body = dto::CreateFieldDto,
+    responses(
+        (status = 200, body = FieldId),
+        (status = 400, body = ApiError),
+        (status = 409, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn create_field(
     data: web::Json<dto::CreateFieldDto>,
     service: Data<FieldService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
-    let field = service
-        .create(&data.name, &data.r#type)
-        .await
-        .map_err(|e| match e {
-            FieldCreateError::AlreadyExists => ApiError::conflict(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+    let field = service.create(&data.name, &data.r#type).await?;
 
-    if field.is_err() {
-        field.unwrap_err()
-    } else {
-        field.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(field))
 }
@@ -1,29 +1,29 @@
 use actix_web::{
     web::{self, Data},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 
 use crate::{
     api::errors::ApiError,
-    services::field::{dto::FieldGetRemoveError, FieldService},
+    services::field::{dto::FieldId, FieldService},
 };
 
+#[utoipa::path(
+    delete,
+    path = "/{field_id}",
+    tag = "fields",
+    params(("field_id" = u32, Path)),
+    responses(
+        (status = 200, body = FieldId),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn delete_field(
     service: Data<FieldService>,
     field_id: web::Path<u32>,
-) -> impl Responder {
-    let result = service
-        .remove(field_id.into_inner())
-        .await
-        .map_err(|e| match e {
-            FieldGetRemoveError::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+) -> Result<HttpResponse, ApiError> {
+    let field = service.remove(field_id.into_inner()).await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(field))
 }
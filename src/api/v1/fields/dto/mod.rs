@@ -1,9 +1,10 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 use validator::{Validate, ValidationError};
 
 use crate::services::field::field_type::FieldType;
 
-#[derive(Deserialize, Clone, Debug, Validate)]
+#[derive(Deserialize, Clone, Debug, Validate, ToSchema)]
 pub struct CreateFieldDto {
     #[validate(length(min = 1))]
     pub name: String,
@@ -16,7 +17,10 @@ fn validate_field_type(value: &str) -> Result<(), ValidationError> {
     let result: FieldType = value.into();
 
     if result == FieldType::Unknown {
-        return Err(ValidationError::new("invalid field type"));
+        let mut err = ValidationError::new("invalid_field_type");
+        err.message = Some("Unknown field type".into());
+
+        return Err(err);
     }
 
     Ok(())
@@ -1,19 +1,19 @@
-use actix_web::{web::Data, HttpResponse, Responder};
+use actix_web::{web::Data, HttpResponse};
 
-use crate::{api::errors::ApiError, services::field::FieldService};
+use crate::{
+    api::errors::ApiError,
+    services::field::{dto::FieldSerializable, FieldService},
+};
 
-pub(super) async fn get_fields(service: Data<FieldService>) -> impl Responder {
-    let result = service
-        .get_all()
-        .await
-        .map_err(|e| match e {
-            _ => ApiError::internal_error(),
-        })
-        .map(|fields| HttpResponse::Ok().json(fields));
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "fields",
+    responses((status = 200, body = Vec<FieldSerializable>)),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_fields(service: Data<FieldService>) -> Result<HttpResponse, ApiError> {
+    let fields = service.get_all().await?;
 
-    if result.is_err() {
-        result.unwrap_err()
-    } else {
-        result.unwrap()
-    }
+    Ok(HttpResponse::Ok().json(fields))
 }
@@ -4,9 +4,18 @@ pub mod dto;
 mod get_fields;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_fields::get_fields,
+    create_fields::create_field,
+    delete_fields::delete_field,
+))]
+pub(super) struct FieldsApi;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(
@@ -1,30 +1,41 @@
 use actix_multipart::form::MultipartForm;
-use actix_web::{web::Data, HttpResponse, Responder};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
 
-use crate::{api::errors::ApiError, config::Config, services::files::{FilesService, FilesServiceErr}};
+use crate::{api::errors::ApiError, config::Config, services::files::FilesService};
 
-use super::dto::UploadForm;
+use super::dto::{UploadForm, UploadQuery, UploadedFile};
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "files",
+    params(UploadQuery),
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    responses(
+        (
+            status = 200,
+            description = "Result of every file, in the order they were sent",
+            body = Vec<UploadedFile>,
+        ),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn create_file(
     MultipartForm(form): MultipartForm<UploadForm>,
+    query: Query<UploadQuery>,
     files_service: Data<FilesService>,
-    config: Data<Config>
-) -> impl Responder {
-    let result = files_service.save_file(form.files, config.as_ref())
-        .await
-        .map_err(|err| match err {
-            FilesServiceErr::NotFound => ApiError::internal_error(),
-            FilesServiceErr::Internal => ApiError::internal_error(),
-            e => {
-                log::error!("{:?}", e); 
-                ApiError::invalid_data()
-            },
-        })
-        .map(|res| HttpResponse::Ok().json(res));
+    config: Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let files: Vec<UploadedFile> = files_service
+        .save_files(form.files, query.purpose, config.as_ref())
+        .await?
+        .into_iter()
+        .map(UploadedFile::from)
+        .collect();
 
-    if let Err(err) = result {
-        return err;
-    }
-
-    result.unwrap()
+    Ok(HttpResponse::Ok().json(files))
 }
@@ -2,9 +2,63 @@ use actix_multipart::form::{
     tempfile::TempFile,
     MultipartForm,
 };
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
-#[derive(Debug, MultipartForm)]
+use crate::{
+    api::errors::ApiError,
+    services::files::{FileName, UploadPurpose, UploadResult},
+};
+
+#[derive(Debug, MultipartForm, ToSchema)]
 pub struct UploadForm {
     #[multipart(rename = "file")]
+    #[serde(rename = "file")]
+    #[schema(value_type = Vec<String>, format = Binary)]
     pub files: Vec<TempFile>,
 }
+
+/// Either `file` with the id of the stored file or `error` with the code
+/// a failed single upload would be answered with.
+#[derive(Serialize, ToSchema)]
+pub struct UploadedFile {
+    name: Option<String>,
+    #[serde(flatten)]
+    file: Option<FileName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'static str>,
+}
+
+impl From<UploadResult> for UploadedFile {
+    fn from(value: UploadResult) -> Self {
+        match value.result {
+            Ok(file) => Self {
+                name: value.name,
+                file: Some(file),
+                error: None,
+            },
+            Err(err) => Self {
+                name: value.name,
+                file: None,
+                error: Some(ApiError::from(err).code()),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FileQuery {
+    /// Width the image is displayed at, the closest thumbnail is served.
+    #[validate(range(min = 1, max = 10000))]
+    pub w: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UploadQuery {
+    /// `image` by default, `document` is for spec sheets and certificates.
+    #[serde(default)]
+    pub purpose: UploadPurpose,
+}
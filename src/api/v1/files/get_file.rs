@@ -1,46 +1,48 @@
+use actix_files::NamedFile;
 use actix_web::{
-    get, http::header::{ContentDisposition, DispositionType}, web::{self, Data}, HttpRequest, Responder
+    get,
+    http::header::{ContentDisposition, DispositionType},
+    web::{self, Data, Query},
 };
-use actix_files::NamedFile;
 use uuid::Uuid;
-
-use crate::{api::errors::ApiError, config::Config, services::files::{FilesService, FilesServiceErr}};
-
+use validator::Validate;
+
+use crate::{api::errors::ApiError, config::Config, services::files::FilesService};
+
+use super::dto::FileQuery;
+
+#[utoipa::path(
+    get,
+    path = "/{filename}",
+    tag = "files",
+    params(("filename" = Uuid, Path), FileQuery),
+    responses(
+        (status = 200, description = "File contents", content_type = "application/octet-stream"),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+)]
 #[get("/{filename:.*}")]
 pub(super) async fn get_file(
-    req: HttpRequest,
-    filename: web::Path<Uuid>, 
+    filename: web::Path<Uuid>,
+    query: Query<FileQuery>,
     file_service: Data<FilesService>,
     config: Data<Config>,
-) -> impl Responder {
-    let uid = filename.into_inner();
-
-    let path = file_service.get_file(uid, config.as_ref())
-        .await
-        .map_err(|err| match err {
-            FilesServiceErr::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error()
-        });
+) -> Result<NamedFile, ApiError> {
+    query.validate()?;
 
-    if path.is_err() {
-        return path.err().unwrap();
-    }
-
-    let path = path.unwrap();
-
-    let file = NamedFile::open(path).map_err(|_| ApiError::internal_error());
+    let uid = filename.into_inner();
+    let (path, file_type) = file_service.get_file(uid, query.w, config.as_ref()).await?;
+    let mut file = NamedFile::open(path).map_err(|_| ApiError::internal_error())?;
 
-    if file.is_err() {
-        return file.err().unwrap();
+    if let Some(file_type) = file_type {
+        file = file.set_content_type(file_type.mime());
     }
 
-    let file = file.unwrap();
-
-    file
+    Ok(file
         .use_last_modified(true)
         .set_content_disposition(ContentDisposition {
             disposition: DispositionType::Attachment,
             parameters: vec![],
-        })
-        .into_response(&req)
+        }))
 }
@@ -3,9 +3,14 @@ mod dto;
 mod get_file;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
 
+#[derive(OpenApi)]
+#[openapi(paths(create_file::create_file, get_file::get_file))]
+pub(super) struct FilesApi;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(
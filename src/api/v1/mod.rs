@@ -1,3 +1,4 @@
+mod antispam;
 mod auth;
 mod categories;
 mod company_services;
@@ -5,13 +6,29 @@ mod fields;
 mod files;
 mod orders;
 mod products;
+mod webhooks;
 
 pub use products::FieldInProductDto;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::config::Config;
 
+#[derive(OpenApi)]
+#[openapi(nest(
+    (path = "/products", api = products::ProductsApi),
+    (path = "/auth", api = auth::AuthApi),
+    (path = "/categories", api = categories::CategoriesApi),
+    (path = "/files", api = files::FilesApi),
+    (path = "/orders", api = orders::OrdersApi),
+    (path = "/fields", api = fields::FieldsApi),
+    (path = "/services", api = company_services::CompanyServicesApi),
+    (path = "/webhooks", api = webhooks::WebhooksApi),
+    (path = "/antispam", api = antispam::AntiSpamApi),
+))]
+pub(super) struct V1Api;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(web::scope("/products").configure(products::configure(config.clone())))
@@ -22,6 +39,8 @@ pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig
             .service(web::scope("/fields").configure(fields::configure(config.clone())))
             .service(
                 web::scope("/services").configure(company_services::configure(config.clone())),
-            );
+            )
+            .service(web::scope("/webhooks").configure(webhooks::configure(config.clone())))
+            .service(web::scope("/antispam").configure(antispam::configure(config.clone())));
     }
 }
@@ -1,45 +1,137 @@
 use actix_web::{
     post,
     web::{Data, Json},
-    HttpResponse, Responder,
+    HttpRequest, HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::{errors::ApiError, v1::orders::dto::CreateOrderDto},
-    services::order::{OrderInsertionErr, OrderService},
+    cache::Cache,
+    services::{
+        antispam::{AntiSpamService, OrderSubmission},
+        idempotency::{IdempotencyErr, IdempotencyKey, IdempotencyState},
+        notification::NotificationService,
+        order::{OrderInsertion, OrderService},
+        phone::PhoneService,
+    },
 };
 
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "orders",
+    params(
+        (
+            "Idempotency-Key" = Option<String>,
+            Header,
+            description = "Retries with the same key and body get the first response",
+        ),
+    ),
+    request_body(
+        content = CreateOrderDto,
+        description = "Also takes `captcha`, the human verification token, when required",
+    ),
+    responses(
+        (status = 200, body = OrderInsertion),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 429, body = ApiError),
+    ),
+)]
 #[post("")]
 pub(super) async fn create_order(
+    req: HttpRequest,
     order_service: Data<OrderService>,
+    notification_service: Data<NotificationService>,
+    antispam_service: Data<AntiSpamService>,
+    phone_service: Data<PhoneService>,
+    cache: Data<Cache>,
     body: Json<CreateOrderDto>,
-) -> impl Responder {
-    if body.validate().is_err() {
-        return ApiError::invalid_data();
+) -> Result<HttpResponse, ApiError> {
+    body.validate()?;
+
+    let phone = phone_service.normalize(&body.phone)?;
+
+    let idempotency_key = match req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(key) => {
+            let key = key.to_str().map_err(|_| IdempotencyErr::InvalidKey)?;
+
+            Some(IdempotencyKey::new(&cache, "orders", key, &body.0)?)
+        }
+        None => None,
+    };
+
+    if let Some(key) = &idempotency_key {
+        match key.begin::<OrderInsertion>() {
+            Ok(IdempotencyState::Started) => {}
+            Ok(IdempotencyState::Replay(insertion)) => {
+                return Ok(HttpResponse::Ok().json(insertion));
+            }
+            Ok(IdempotencyState::InProgress | IdempotencyState::Conflict) => {
+                return Err(ApiError::conflict());
+            }
+            // Losing the cache shouldn't stop customers from ordering.
+            Err(err) => log::error!("Idempotency key skipped: {:?}", err),
+        }
     }
 
+    // Checked after the idempotency key so that retries don't use up the rate limit.
+    let ip = antispam_service.client_ip(&req.connection_info());
+    let submission = OrderSubmission {
+        ip: ip.as_deref(),
+        phone: &phone,
+        honeypot: &body.website,
+        token: body.captcha.as_deref(),
+    };
+
+    let checked = antispam_service.check(&cache, &submission);
+
+    if let Err(reason) = checked {
+        if let Some(key) = &idempotency_key {
+            key.abort();
+        }
+
+        return Err(reason.into());
+    }
+
+    let body = body.into_inner();
     let order = order_service
         .create(
-            body.0.name,
-            body.0.surname,
-            body.0.phone,
-            body.0.address,
-            body.0.products,
+            body.name,
+            body.surname,
+            phone,
+            body.email,
+            body.address,
+            body.products,
         )
-        .await
-        .map_err(|err| {
-            if let OrderInsertionErr::ProductNotFound(ids) = err {
-                return ApiError::not_found_ids(ids);
-            }
-
-            ApiError::internal_error()
-        })
-        .map(|res| HttpResponse::Ok().json(res));
+        .await;
 
     if let Err(err) = order {
-        return err;
+        if let Some(key) = &idempotency_key {
+            key.abort();
+        }
+
+        return Err(err.into());
     }
 
-    order.unwrap()
+    let order = order.unwrap();
+
+    if let Some(key) = &idempotency_key {
+        key.complete(&order);
+    }
+
+    let id = order.id;
+
+    actix_web::rt::spawn(async move {
+        match order_service.get(id).await {
+            Ok(order) => notification_service.order_created(&order),
+            Err(err) => log::error!("Order {} notification skipped: {:?}", id, err),
+        }
+    });
+
+    Ok(HttpResponse::Ok().json(order))
 }
@@ -1,9 +1,13 @@
-use serde::Deserialize;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-use crate::services::order::ProductWithQuantity;
+use crate::services::order::{
+    order_status::OrderStatus, OrderFilter, OrderSort, ProductWithQuantity,
+};
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct CreateOrderDto {
     #[validate(length(min = 1))]
     pub name: String,
@@ -14,8 +18,115 @@ pub struct CreateOrderDto {
     #[validate(length(min = 1))]
     pub phone: String,
 
+    #[validate(email)]
+    pub email: Option<String>,
+
     #[validate(length(min = 1))]
     pub address: String,
 
     pub products: Vec<ProductWithQuantity>,
+
+    /// Honeypot, hidden from humans by the storefront and expected to stay empty.
+    #[serde(default, skip_serializing)]
+    pub website: String,
+
+    /// Captcha response or proof of work stamp, see `HumanVerifier`.
+    #[serde(skip_serializing)]
+    pub captcha: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, ToSchema)]
+pub struct UpdateOrderStatusDto {
+    pub status: OrderStatus,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetOrdersQuery {
+    /// Bounded so that the offset of the page can't overflow.
+    #[validate(range(min = 1, max = 1000000))]
+    #[serde(default = "default_page")]
+    pub page: u64,
+
+    #[validate(range(min = 1, max = 100))]
+    #[serde(default = "default_page_size")]
+    pub page_size: u64,
+
+    pub status: Option<OrderStatus>,
+
+    pub from: Option<NaiveDate>,
+
+    pub to: Option<NaiveDate>,
+
+    #[validate(length(min = 1))]
+    pub phone: Option<String>,
+
+    #[validate(length(min = 1))]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub sort: OrderSort,
+}
+
+pub fn default_page() -> u64 {
+    1
+}
+
+pub fn default_page_size() -> u64 {
+    15
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, ToSchema)]
+pub enum ExportFormat {
+    #[serde(rename = "csv")]
+    Csv,
+    #[serde(rename = "xlsx")]
+    Xlsx,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportOrdersQuery {
+    pub format: ExportFormat,
+
+    pub status: Option<OrderStatus>,
+
+    pub from: Option<NaiveDate>,
+
+    pub to: Option<NaiveDate>,
+
+    #[validate(length(min = 1))]
+    pub phone: Option<String>,
+
+    #[validate(length(min = 1))]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub sort: OrderSort,
+}
+
+impl From<GetOrdersQuery> for OrderFilter {
+    fn from(value: GetOrdersQuery) -> Self {
+        Self {
+            status: value.status,
+            from: value.from,
+            to: value.to,
+            phone: value.phone,
+            name: value.name,
+            sort: value.sort,
+        }
+    }
+}
+
+impl From<ExportOrdersQuery> for OrderFilter {
+    fn from(value: ExportOrdersQuery) -> Self {
+        Self {
+            status: value.status,
+            from: value.from,
+            to: value.to,
+            phone: value.phone,
+            name: value.name,
+            sort: value.sort,
+        }
+    }
 }
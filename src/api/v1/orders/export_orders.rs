@@ -0,0 +1,111 @@
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::{Bytes, Data, Query},
+    HttpResponse,
+};
+use futures_util::stream;
+use validator::Validate;
+
+use crate::{
+    api::{
+        errors::ApiError,
+        v1::orders::dto::{ExportFormat, ExportOrdersQuery},
+    },
+    services::{
+        order::{
+            export::{lines_to_csv, lines_to_xlsx},
+            OrderFilter, OrderService,
+        },
+        phone::PhoneService,
+    },
+};
+
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+fn attachment(filename: &str) -> ContentDisposition {
+    ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(filename.to_owned())],
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/export",
+    tag = "orders",
+    params(ExportOrdersQuery),
+    responses(
+        (status = 200, content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn export_orders(
+    order_service: Data<OrderService>,
+    phone_service: Data<PhoneService>,
+    query: Query<ExportOrdersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    query.validate()?;
+
+    let format = query.format;
+    let mut filter = OrderFilter::from(query.into_inner());
+    filter.phone = filter.phone.map(|phone| phone_service.search_term(&phone));
+
+    let ids = order_service.export_ids(&filter).await?;
+
+    let response = match format {
+        ExportFormat::Csv => {
+            let batches: Vec<Vec<i32>> = ids
+                .chunks(OrderService::EXPORT_BATCH_SIZE)
+                .map(<[i32]>::to_vec)
+                .collect();
+
+            // The header goes out even when nothing matches the filter.
+            let body = stream::unfold((batches.into_iter(), true), move |(mut batches, first)| {
+                let order_service = order_service.clone();
+
+                async move {
+                    let batch = batches.next();
+
+                    if batch.is_none() && !first {
+                        return None;
+                    }
+
+                    let lines = match batch {
+                        Some(batch) => order_service.export_lines(&batch).await,
+                        None => Ok(Vec::new()),
+                    };
+
+                    let chunk = lines
+                        .map_err(ApiError::from)
+                        .and_then(|lines| lines_to_csv(&lines, first).map_err(ApiError::from))
+                        .map(Bytes::from);
+
+                    Some((chunk, (batches, false)))
+                }
+            });
+
+            HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(attachment("orders.csv"))
+                .streaming(body)
+        }
+        ExportFormat::Xlsx => {
+            let mut lines = Vec::new();
+
+            for batch in ids.chunks(OrderService::EXPORT_BATCH_SIZE) {
+                lines.extend(order_service.export_lines(batch).await?);
+            }
+
+            HttpResponse::Ok()
+                .content_type(XLSX_CONTENT_TYPE)
+                .insert_header(attachment("orders.xlsx"))
+                .body(lines_to_xlsx(&lines)?)
+        }
+    };
+
+    Ok(response)
+}
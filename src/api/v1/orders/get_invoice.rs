@@ -0,0 +1,61 @@
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::{Data, Path},
+    HttpResponse,
+};
+
+use crate::{api::errors::ApiError, services::invoice::InvoiceService};
+
+#[utoipa::path(
+    get,
+    path = "/{id}/invoice.pdf",
+    tag = "orders",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = Vec<u8>, content_type = "application/pdf"),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_invoice_pdf(
+    id: Path<u32>,
+    invoice_service: Data<InvoiceService>,
+) -> Result<HttpResponse, ApiError> {
+    let id = id.into_inner();
+    let body = invoice_service.pdf(id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Inline,
+            parameters: vec![DispositionParam::Filename(format!("invoice-{id}.pdf"))],
+        })
+        .body(body))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/invoice.html",
+    tag = "orders",
+    params(("id" = u32, Path)),
+    responses(
+        (
+            status = 200,
+            description = "Preview, the number is `DRAFT` until the PDF is requested",
+            body = String,
+            content_type = "text/html",
+        ),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_invoice_html(
+    id: Path<u32>,
+    invoice_service: Data<InvoiceService>,
+) -> Result<HttpResponse, ApiError> {
+    let body = invoice_service.html(id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(body))
+}
@@ -1,15 +1,84 @@
-use actix_web::{web::Data, HttpResponse, Responder};
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use validator::Validate;
 
-use crate::{api::errors::ApiError, services::order::OrderService};
+use crate::{
+    api::{errors::ApiError, v1::orders::dto::GetOrdersQuery},
+    services::{
+        order::{
+            OrderFilter, OrderSerializable, OrderService, OrderStatusHistorySerializable,
+            OrdersPage,
+        },
+        phone::PhoneService,
+    },
+};
 
-pub(super) async fn get_orders(order_service: Data<OrderService>) -> impl Responder {
-    let categories = order_service.get_all().await;
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "orders",
+    params(GetOrdersQuery),
+    responses(
+        (status = 200, body = OrdersPage),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_orders(
+    order_service: Data<OrderService>,
+    phone_service: Data<PhoneService>,
+    query: Query<GetOrdersQuery>,
+) -> Result<HttpResponse, ApiError> {
+    query.validate()?;
 
-    if categories.is_err() {
-        return ApiError::internal_error();
-    }
+    let page = query.page - 1;
+    let page_size = query.page_size;
+    let mut filter = OrderFilter::from(query.into_inner());
+    filter.phone = filter.phone.map(|phone| phone_service.search_term(&phone));
 
-    categories
-        .map(|data| HttpResponse::Ok().json(data))
-        .unwrap()
+    let orders = order_service.get_all(&filter, page, page_size).await?;
+
+    Ok(HttpResponse::Ok().json(orders))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "orders",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = OrderSerializable),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_order(
+    id: Path<u32>,
+    order_service: Data<OrderService>,
+) -> Result<HttpResponse, ApiError> {
+    let order = order_service.get(id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(order))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/status",
+    tag = "orders",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = Vec<OrderStatusHistorySerializable>),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_order_status_history(
+    id: Path<u32>,
+    order_service: Data<OrderService>,
+) -> Result<HttpResponse, ApiError> {
+    let history = order_service.status_history(id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(history))
 }
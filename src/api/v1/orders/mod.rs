@@ -1,17 +1,65 @@
 mod create_order;
 mod dto;
+mod export_orders;
+mod get_invoice;
 mod get_orders;
+mod update_order_status;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
-use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
+use crate::{api::middlewares::authenticate::JwtAuth, config::Config, services::order::OrderSort};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_order::create_order,
+        get_orders::get_orders,
+        export_orders::export_orders,
+        get_orders::get_order,
+        get_orders::get_order_status_history,
+        update_order_status::update_order_status,
+        get_invoice::get_invoice_pdf,
+        get_invoice::get_invoice_html,
+    ),
+    // Only used in query strings, which don't register their schemas.
+    components(schemas(dto::ExportFormat, OrderSort)),
+)]
+pub(super) struct OrdersApi;
 
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
-        cfg.service(create_order::create_order).service(
-            web::resource("")
-                .wrap(JwtAuth::new(config.clone()))
-                .get(get_orders::get_orders),
-        );
+        cfg.service(create_order::create_order)
+            .service(
+                web::resource("")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(get_orders::get_orders),
+            )
+            .service(
+                web::resource("export")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(export_orders::export_orders),
+            )
+            .service(
+                web::resource("{id}")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(get_orders::get_order),
+            )
+            .service(
+                web::resource("{id}/status")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .patch(update_order_status::update_order_status)
+                    .get(get_orders::get_order_status_history),
+            )
+            .service(
+                web::resource("{id}/invoice.pdf")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(get_invoice::get_invoice_pdf),
+            )
+            .service(
+                web::resource("{id}/invoice.html")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(get_invoice::get_invoice_html),
+            );
     }
 }
@@ -0,0 +1,38 @@
+use actix_web::{
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+
+use crate::{
+    api::{errors::ApiError, v1::orders::dto::UpdateOrderStatusDto},
+    services::{
+        auth::JwtAccessData,
+        order::{OrderService, OrderStatusUpdate},
+    },
+};
+
+#[utoipa::path(
+    patch,
+    path = "/{id}/status",
+    tag = "orders",
+    params(("id" = u32, Path)),
+    request_body = UpdateOrderStatusDto,
+    responses(
+        (status = 200, body = OrderStatusUpdate),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn update_order_status(
+    id: Path<u32>,
+    body: Json<UpdateOrderStatusDto>,
+    user: ReqData<JwtAccessData>,
+    order_service: Data<OrderService>,
+) -> Result<HttpResponse, ApiError> {
+    let result = order_service
+        .update_status(id.into_inner(), body.status, user.id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -1,43 +1,97 @@
 use actix_web::{
-    web::{Data, Json},
-    HttpResponse, Responder,
+    web::{Data, Json, Path},
+    HttpResponse,
 };
 use rust_decimal::Decimal;
 use validator::Validate;
 
-use crate::{api::errors::ApiError, services::product::ProductService};
+use crate::{
+    api::errors::ApiError,
+    services::product::{ProductInsertionUpdate, ProductService},
+};
 
-use super::dto::CreateProductsDto;
+use super::dto::{CreateProductsDto, CreateVariantDto};
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "products",
+    request_body = CreateProductsDto,
+    responses(
+        (status = 200, body = ProductInsertionUpdate),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn create_product(
     data: Json<CreateProductsDto>,
     product_service: Data<ProductService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
     if data.0.price < Decimal::new(0, 0) {
-        return ApiError::invalid_data();
+        return Err(ApiError::invalid_field(
+            "price",
+            "range",
+            "Must not be negative",
+        ));
     }
 
-    let result = product_service
+    let product = product_service
         .create(
             data.0.name,
             data.0.price,
             data.0.article,
             data.0.description,
             data.0.photo,
+            data.0.stock,
             data.0.fields,
             data.0.category_id,
         )
-        .await
-        .map(|value| HttpResponse::Ok().json(value))
-        .map_err(|_| ApiError::internal_error());
+        .await?;
+
+    Ok(HttpResponse::Ok().json(product))
+}
 
-    if result.is_err() {
-        return result.err().unwrap();
+#[utoipa::path(
+    post,
+    path = "/{id}/variants",
+    tag = "products",
+    params(("id" = u32, Path, description = "Parent product")),
+    request_body = CreateVariantDto,
+    responses(
+        (status = 200, body = ProductInsertionUpdate),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn create_variant(
+    id: Path<u32>,
+    data: Json<CreateVariantDto>,
+    product_service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
+
+    if data.0.price < Decimal::new(0, 0) {
+        return Err(ApiError::invalid_field(
+            "price",
+            "range",
+            "Must not be negative",
+        ));
     }
 
-    result.unwrap()
+    let variant = product_service
+        .create_variant(
+            id.into_inner(),
+            data.0.name,
+            data.0.price,
+            data.0.article,
+            data.0.photo,
+            data.0.stock,
+            data.0.fields,
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(variant))
 }
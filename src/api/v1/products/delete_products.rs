@@ -1,51 +1,60 @@
 use actix_web::{
     web::{Data, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::{errors::ApiError, v1::products::dto::DeleteProductsDto},
-    services::product::{ProductService, ProductServiceErr},
+    services::product::{ProductIdx, ProductService},
 };
 
+#[utoipa::path(
+    delete,
+    path = "/{product_id}/fields/{field_id}",
+    tag = "products",
+    params(("product_id" = u32, Path), ("field_id" = u32, Path)),
+    responses(
+        (status = 200),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn delete_field_from_product(
     product_id: Path<u32>,
     field_id: Path<u32>,
     service: Data<ProductService>,
-) -> impl Responder {
-    let result = service
+) -> Result<HttpResponse, ApiError> {
+    service
         .remove_field_from_product(product_id.into_inner(), field_id.into_inner())
-        .await
-        .map_err(|e| match e {
-            ProductServiceErr::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+        .await?;
 
-    if result.is_err() {
-        return result.unwrap_err();
-    } else {
-        return result.unwrap();
-    }
+    Ok(HttpResponse::Ok().json(()))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "products",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = ProductIdx),
+        (status = 404, body = ApiError),
+        (
+            status = 409,
+            description = "The product still has variants",
+            body = ApiError,
+        ),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn delete_products(
     data: Path<DeleteProductsDto>,
     product_service: Data<ProductService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
-    let deletion_result = product_service
-        .delete(&[data.id])
-        .await
-        .map(|data| HttpResponse::Ok().json(data));
+    let deleted = product_service.delete(&[data.id]).await?;
 
-    if deletion_result.is_err() {
-        return ApiError::internal_error();
-    }
-
-    deletion_result.unwrap()
+    Ok(HttpResponse::Ok().json(deleted))
 }
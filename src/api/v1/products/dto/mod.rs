@@ -1,26 +1,34 @@
+use std::collections::{BTreeMap, HashMap};
+
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::{
+    api::errors::ApiError,
+    services::product::filter::{FieldCondition, FieldFilter},
+};
+
 #[derive(Deserialize, Validate, Debug, Clone)]
 pub struct DeleteProductsDto {
     pub id: u32,
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct FieldInProductDto {
     pub id: u32,
     #[validate(length(min = 1))]
     pub value: String,
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct FieldInProductAddOrUpdate {
     #[validate(length(min = 1))]
     pub value: String,
 }
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct UpdateProductsDto {
     #[validate(length(min = 3))]
     pub name: String,
@@ -33,12 +41,21 @@ pub struct UpdateProductsDto {
     #[validate(length(min = 1))]
     pub description: String,
 
+    /// Becomes the main image of the gallery, without it the gallery is left as is.
     pub photo: Option<Uuid>,
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, ToSchema)]
+pub struct StockAdjustmentDto {
+    /// Units received, negative for units written off.
+    pub delta: i32,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchProductsQuery {
-    #[validate(range(min = 1))]
+    /// Bounded so that the offset of the page can't overflow.
+    #[validate(range(min = 1, max = 1000000))]
     #[serde(default = "default_page")]
     pub page: u64,
 
@@ -50,7 +67,92 @@ pub fn default_page() -> u64 {
     1
 }
 
-#[derive(Deserialize, Validate, Debug, Clone)]
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FacetsQuery {
+    pub category_id: Option<u32>,
+
+    #[serde(default)]
+    pub query: String,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuggestQuery {
+    #[validate(length(max = 100))]
+    #[serde(default)]
+    pub q: String,
+
+    #[validate(range(min = 1, max = 20))]
+    #[serde(default = "default_suggestions_limit")]
+    pub limit: u64,
+}
+
+pub fn default_suggestions_limit() -> u64 {
+    10
+}
+
+/// Field filters from the query string: `field.{id}=value` for an exact value,
+/// `field.{id}.min=100` and `field.{id}.max=200` for a range of an integer field.
+pub fn field_filters(params: &HashMap<String, String>) -> Result<Vec<FieldFilter>, ApiError> {
+    let mut filters = Vec::new();
+    let mut ranges: BTreeMap<u32, (Option<String>, Option<String>)> = BTreeMap::new();
+
+    for (key, value) in params {
+        let Some(filter) = key.strip_prefix("field.") else {
+            continue;
+        };
+
+        let (id, bound) = match filter.split_once('.') {
+            Some((id, bound)) => (id, Some(bound)),
+            None => (filter, None),
+        };
+
+        let invalid = || {
+            ApiError::invalid_field(
+                key,
+                "invalid_field_filter",
+                "Expected field.{id}, field.{id}.min or field.{id}.max",
+            )
+        };
+        let field_id = id.parse::<u32>().map_err(|_| invalid())?;
+
+        match bound {
+            None => filters.push(FieldFilter {
+                field_id,
+                condition: FieldCondition::Equals(value.to_owned()),
+            }),
+            Some("min") => ranges.entry(field_id).or_default().0 = Some(value.to_owned()),
+            Some("max") => ranges.entry(field_id).or_default().1 = Some(value.to_owned()),
+            Some(_) => return Err(invalid()),
+        }
+    }
+
+    filters.extend(
+        ranges
+            .into_iter()
+            .map(|(field_id, (min, max))| FieldFilter {
+                field_id,
+                condition: FieldCondition::Range { min, max },
+            }),
+    );
+
+    Ok(filters)
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LowStockQuery {
+    #[validate(range(max = 2147483647))]
+    #[serde(default = "default_low_stock_threshold")]
+    pub threshold: u32,
+}
+
+pub fn default_low_stock_threshold() -> u32 {
+    5
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
 pub struct CreateProductsDto {
     #[validate(length(min = 3))]
     pub name: String,
@@ -63,9 +165,40 @@ pub struct CreateProductsDto {
     #[validate(length(min = 1))]
     pub description: String,
 
+    /// Becomes the main image of the gallery, without it the gallery is left as is.
     pub photo: Option<Uuid>,
 
+    #[validate(range(max = 2147483647))]
+    pub stock: Option<u32>,
+
     pub category_id: u32,
 
     pub fields: Vec<FieldInProductDto>,
 }
+
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
+pub struct CreateVariantDto {
+    /// Tells the variant apart from the others, e.g. `120 cm` or `white oak`.
+    #[validate(length(min = 1))]
+    pub name: String,
+
+    pub price: Decimal,
+
+    #[validate(length(min = 1))]
+    pub article: String,
+
+    /// Becomes the main image of the variant's gallery.
+    pub photo: Option<Uuid>,
+
+    #[validate(range(max = 2147483647))]
+    pub stock: Option<u32>,
+
+    #[serde(default)]
+    pub fields: Vec<FieldInProductDto>,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
+pub struct ProductImagesDto {
+    #[validate(length(min = 1, max = 50))]
+    pub files: Vec<Uuid>,
+}
@@ -1,58 +1,157 @@
+use std::collections::HashMap;
+
 use crate::{
     api::errors::ApiError,
-    services::product::{ProductService, ProductServiceErr},
+    cache::Cache,
+    services::product::{
+        filter::FieldFacet, search::ProductSearchPage, suggest::Suggestion, ProductSerializable,
+        ProductService, ProductsPage,
+    },
 };
 use actix_web::{
     get,
     web::{Data, Path, Query},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
-use super::dto::SearchProductsQuery;
+use super::dto::{field_filters, FacetsQuery, LowStockQuery, SearchProductsQuery, SuggestQuery};
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "products",
+    params(
+        SearchProductsQuery,
+        (
+            "field.{id}" = Option<String>,
+            Query,
+            description = "Exact value of a field, `field.{id}.min` and `field.{id}.max` \
+                bound integer fields",
+        ),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Products, or search hits by relevance when `query` is given",
+            content(
+                (ProductsPage = "application/json"),
+                (ProductSearchPage = "application/json"),
+            ),
+        ),
+        (status = 400, body = ApiError),
+    ),
+)]
 #[get("")]
 pub(super) async fn get_products(
     products_service: Data<ProductService>,
     query: Query<SearchProductsQuery>,
-) -> impl Responder {
-    if query.0.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+    params: Query<HashMap<String, String>>,
+) -> Result<HttpResponse, ApiError> {
+    query.0.validate()?;
+
+    let filters = field_filters(&params)?;
 
-    let products = if query.0.query.len() == 0 {
-        products_service.all(query.page - 1).await
-    } else {
-        println!("Search: {}", query.0.query.to_lowercase());
-        products_service
-            .search(&query.0.query.to_lowercase(), query.page - 1)
-            .await
-    };
-
-    if products.is_err() {
-        return ApiError::internal_error();
+    if query.query.is_empty() {
+        let page = products_service.all(&filters, query.page - 1).await?;
+
+        return Ok(HttpResponse::Ok().json(page));
     }
 
-    products.map(|data| HttpResponse::Ok().json(data)).unwrap()
+    let page = products_service
+        .search(&query.query, &filters, query.page - 1)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(page))
 }
 
+#[utoipa::path(
+    get,
+    path = "/facets",
+    tag = "products",
+    params(FacetsQuery),
+    responses((status = 200, body = Vec<FieldFacet>)),
+)]
+#[get("facets")]
+pub(super) async fn get_facets(
+    query: Query<FacetsQuery>,
+    product_service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    let facets = product_service
+        .facets(query.category_id, &query.query)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(facets))
+}
+
+#[utoipa::path(
+    get,
+    path = "/suggest",
+    tag = "products",
+    params(SuggestQuery),
+    responses(
+        (
+            status = 200,
+            description = "Closest product names, articles and category names, \
+                typos and the wrong keyboard layout are tolerated",
+            body = Vec<Suggestion>,
+        ),
+        (status = 400, body = ApiError),
+    ),
+)]
+#[get("suggest")]
+pub(super) async fn get_suggestions(
+    query: Query<SuggestQuery>,
+    product_service: Data<ProductService>,
+    cache: Data<Cache>,
+) -> Result<HttpResponse, ApiError> {
+    query.0.validate()?;
+
+    let suggestions = product_service
+        .suggest(&cache, &query.q, query.limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(suggestions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    tag = "products",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = ProductSerializable),
+        (status = 404, body = ApiError),
+    ),
+)]
 #[get("{id}")]
 pub(super) async fn get_concreate_product(
     id: Path<u32>,
     product_service: Data<ProductService>,
-) -> impl Responder {
-    let product = product_service
-        .get(id.into_inner())
-        .await
-        .map(|data| HttpResponse::Ok().json(data))
-        .map_err(|err| match err {
-            ProductServiceErr::Internal => ApiError::internal_error(),
-            ProductServiceErr::NotFound => ApiError::not_found(),
-        });
-
-    if product.is_err() {
-        return product.err().unwrap();
-    }
+) -> Result<HttpResponse, ApiError> {
+    let product = product_service.get(id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(product))
+}
+
+#[utoipa::path(
+    get,
+    path = "/low-stock",
+    tag = "products",
+    params(LowStockQuery),
+    responses(
+        (status = 200, body = Vec<ProductSerializable>),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_low_stock_products(
+    query: Query<LowStockQuery>,
+    product_service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    query.0.validate()?;
+
+    let products = product_service.low_stock(query.threshold).await?;
 
-    product.unwrap()
+    Ok(HttpResponse::Ok().json(products))
 }
@@ -2,17 +2,46 @@ mod create_product;
 mod delete_products;
 mod dto;
 mod get_products;
+mod product_images;
 mod update_product;
 
 pub use dto::FieldInProductDto;
 
 use actix_web::web::{self, Data};
+use utoipa::OpenApi;
 
 use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_products::get_products,
+    get_products::get_low_stock_products,
+    get_products::get_facets,
+    get_products::get_suggestions,
+    get_products::get_concreate_product,
+    create_product::create_product,
+    create_product::create_variant,
+    delete_products::delete_products,
+    update_product::update_product,
+    update_product::adjust_stock,
+    update_product::add_or_update_field_to_product,
+    delete_products::delete_field_from_product,
+    product_images::attach_images,
+    product_images::reorder_images,
+    product_images::detach_image,
+))]
+pub(super) struct ProductsApi;
+
 pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
     move |cfg| {
         cfg.service(get_products::get_products)
+            .service(
+                web::resource("low-stock")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .get(get_products::get_low_stock_products),
+            )
+            .service(get_products::get_facets)
+            .service(get_products::get_suggestions)
             .service(get_products::get_concreate_product)
             .service(
                 web::resource("")
@@ -25,6 +54,27 @@ pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig
                     .delete(delete_products::delete_products)
                     .patch(update_product::update_product),
             )
+            .service(
+                web::resource("{id}/stock")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .patch(update_product::adjust_stock),
+            )
+            .service(
+                web::resource("{id}/variants")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .post(create_product::create_variant),
+            )
+            .service(
+                web::resource("{id}/images")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .post(product_images::attach_images)
+                    .patch(product_images::reorder_images),
+            )
+            .service(
+                web::resource("{id}/images/{file_id}")
+                    .wrap(JwtAuth::new(config.clone()))
+                    .delete(product_images::detach_image),
+            )
             .service(
                 web::resource("{product_id}/fields/{field_id}")
                     .wrap(JwtAuth::new(config.clone()))
@@ -0,0 +1,90 @@
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpResponse,
+};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    api::errors::ApiError,
+    services::product::{images::ProductImages, ProductService},
+};
+
+use super::dto::ProductImagesDto;
+
+#[utoipa::path(
+    post,
+    path = "/{id}/images",
+    tag = "products",
+    params(("id" = u32, Path)),
+    request_body(
+        content = ProductImagesDto,
+        description = "Files added to the end of the gallery",
+    ),
+    responses(
+        (status = 200, body = ProductImages),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn attach_images(
+    id: Path<u32>,
+    data: Json<ProductImagesDto>,
+    service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
+
+    let images = service.attach_images(id.into_inner(), &data.files).await?;
+
+    Ok(HttpResponse::Ok().json(images))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{id}/images",
+    tag = "products",
+    params(("id" = u32, Path)),
+    request_body(
+        content = ProductImagesDto,
+        description = "Every file of the gallery in the new order, the first one is the main image",
+    ),
+    responses(
+        (status = 200, body = ProductImages),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn reorder_images(
+    id: Path<u32>,
+    data: Json<ProductImagesDto>,
+    service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
+
+    let images = service.reorder_images(id.into_inner(), &data.files).await?;
+
+    Ok(HttpResponse::Ok().json(images))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}/images/{file_id}",
+    tag = "products",
+    params(("id" = u32, Path), ("file_id" = Uuid, Path)),
+    responses(
+        (status = 200, body = ProductImages),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn detach_image(
+    path: Path<(u32, Uuid)>,
+    service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    let (id, file_id) = path.into_inner();
+    let images = service.detach_image(id, file_id).await?;
+
+    Ok(HttpResponse::Ok().json(images))
+}
@@ -1,50 +1,64 @@
 use actix_web::{
     web::{Data, Json, Path},
-    HttpResponse, Responder,
+    HttpResponse,
 };
 use validator::Validate;
 
 use crate::{
     api::errors::ApiError,
-    services::product::{ProductService, ProductServiceErr},
+    services::product::{
+        ProductAddFieldUpdate, ProductInsertionUpdate, ProductService, ProductStock,
+    },
 };
 
-use super::dto::{CreateProductsDto, FieldInProductAddOrUpdate, UpdateProductsDto};
+use super::dto::{FieldInProductAddOrUpdate, StockAdjustmentDto, UpdateProductsDto};
 
+#[utoipa::path(
+    patch,
+    path = "/{product_id}/fields/{field_id}",
+    tag = "products",
+    params(("product_id" = u32, Path), ("field_id" = u32, Path)),
+    request_body = FieldInProductAddOrUpdate,
+    responses(
+        (status = 200, body = ProductAddFieldUpdate),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn add_or_update_field_to_product(
     product_id: Path<u32>,
     field_id: Path<u32>,
     data: Json<FieldInProductAddOrUpdate>,
     service: Data<ProductService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
     let result = service
         .add_or_update_field_to_product(product_id.into_inner(), field_id.into_inner(), &data.value)
-        .await
-        .map_err(|err| match err {
-            ProductServiceErr::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+        .await?;
 
-    if result.is_err() {
-        return result.unwrap_err();
-    } else {
-        return result.unwrap();
-    }
+    Ok(HttpResponse::Ok().json(result))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "products",
+    params(("id" = u32, Path)),
+    request_body = UpdateProductsDto,
+    responses(
+        (status = 200, body = ProductInsertionUpdate),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
 pub(super) async fn update_product(
     id: Path<u32>,
     data: Json<UpdateProductsDto>,
     service: Data<ProductService>,
-) -> impl Responder {
-    if data.validate().is_err() {
-        return ApiError::invalid_data();
-    }
+) -> Result<HttpResponse, ApiError> {
+    data.validate()?;
 
     let result = service
         .update(
@@ -55,16 +69,30 @@ pub(super) async fn update_product(
             &data.description,
             data.photo,
         )
-        .await
-        .map_err(|err| match err {
-            ProductServiceErr::NotFound => ApiError::not_found(),
-            _ => ApiError::internal_error(),
-        })
-        .map(|result| HttpResponse::Ok().json(result));
+        .await?;
 
-    if result.is_err() {
-        return result.unwrap_err();
-    } else {
-        return result.unwrap();
-    }
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{id}/stock",
+    tag = "products",
+    params(("id" = u32, Path)),
+    request_body = StockAdjustmentDto,
+    responses(
+        (status = 200, body = ProductStock),
+        (status = 404, body = ApiError),
+        (status = 409, description = "Fewer units in stock than written off", body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn adjust_stock(
+    id: Path<u32>,
+    data: Json<StockAdjustmentDto>,
+    service: Data<ProductService>,
+) -> Result<HttpResponse, ApiError> {
+    let stock = service.adjust_stock(id.into_inner(), data.delta).await?;
+
+    Ok(HttpResponse::Ok().json(stock))
 }
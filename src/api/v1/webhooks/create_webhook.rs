@@ -0,0 +1,37 @@
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use validator::Validate;
+
+use crate::{
+    api::errors::ApiError,
+    services::webhook::{WebhookSerializable, WebhookService},
+};
+
+use super::dto::CreateWebhookDto;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "webhooks",
+    request_body = CreateWebhookDto,
+    responses(
+        (status = 200, body = WebhookSerializable),
+        (status = 400, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn create_webhook(
+    body: Json<CreateWebhookDto>,
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    body.validate()?;
+
+    let body = body.into_inner();
+    let webhook = webhook_service
+        .create(&body.url, body.event, body.secret)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
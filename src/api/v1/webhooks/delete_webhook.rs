@@ -0,0 +1,29 @@
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+
+use crate::{
+    api::errors::ApiError,
+    services::webhook::{WebhookSerializable, WebhookService},
+};
+
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    tag = "webhooks",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 200, body = WebhookSerializable),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn delete_webhook(
+    id: Path<u32>,
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    let webhook = webhook_service.delete(id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
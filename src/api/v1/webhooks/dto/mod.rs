@@ -0,0 +1,39 @@
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::services::webhook::webhook_event::WebhookEvent;
+
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
+pub struct CreateWebhookDto {
+    #[validate(url)]
+    pub url: String,
+
+    pub event: WebhookEvent,
+
+    #[validate(length(min = 16, max = 256))]
+    pub secret: Option<String>,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, ToSchema)]
+pub struct UpdateWebhookDto {
+    #[validate(url)]
+    pub url: Option<String>,
+
+    pub event: Option<WebhookEvent>,
+
+    pub active: Option<bool>,
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetDeliveriesQuery {
+    /// Bounded so that the offset of the page can't overflow.
+    #[validate(range(min = 1, max = 1000000))]
+    #[serde(default = "default_page")]
+    pub page: u64,
+}
+
+pub fn default_page() -> u64 {
+    1
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpResponse,
+};
+use validator::Validate;
+
+use crate::{
+    api::errors::ApiError,
+    services::webhook::{WebhookDeliverySerializable, WebhookSerializable, WebhookService},
+};
+
+use super::dto::GetDeliveriesQuery;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "webhooks",
+    responses((status = 200, body = Vec<WebhookSerializable>)),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_webhooks(
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    let webhooks = webhook_service.all().await?;
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/deliveries",
+    tag = "webhooks",
+    params(("id" = u32, Path), GetDeliveriesQuery),
+    responses(
+        (status = 200, body = Vec<WebhookDeliverySerializable>),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn get_webhook_deliveries(
+    id: Path<u32>,
+    query: Query<GetDeliveriesQuery>,
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    query.validate()?;
+
+    let deliveries = webhook_service
+        .deliveries(id.into_inner(), query.page - 1)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
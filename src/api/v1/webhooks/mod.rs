@@ -0,0 +1,49 @@
+mod create_webhook;
+mod delete_webhook;
+mod dto;
+mod get_webhooks;
+mod replay_delivery;
+mod update_webhook;
+
+use actix_web::web::{self, Data};
+use utoipa::OpenApi;
+
+use crate::{api::middlewares::authenticate::JwtAuth, config::Config};
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_webhooks::get_webhooks,
+    create_webhook::create_webhook,
+    replay_delivery::replay_delivery,
+    update_webhook::update_webhook,
+    delete_webhook::delete_webhook,
+    get_webhooks::get_webhook_deliveries,
+))]
+pub(super) struct WebhooksApi;
+
+pub(super) fn configure(config: Data<Config>) -> impl Fn(&mut web::ServiceConfig) {
+    move |cfg| {
+        cfg.service(
+            web::resource("")
+                .wrap(JwtAuth::new(config.clone()))
+                .get(get_webhooks::get_webhooks)
+                .post(create_webhook::create_webhook),
+        )
+        .service(
+            web::resource("deliveries/{id}/replay")
+                .wrap(JwtAuth::new(config.clone()))
+                .post(replay_delivery::replay_delivery),
+        )
+        .service(
+            web::resource("{id}")
+                .wrap(JwtAuth::new(config.clone()))
+                .patch(update_webhook::update_webhook)
+                .delete(delete_webhook::delete_webhook),
+        )
+        .service(
+            web::resource("{id}/deliveries")
+                .wrap(JwtAuth::new(config.clone()))
+                .get(get_webhooks::get_webhook_deliveries),
+        );
+    }
+}
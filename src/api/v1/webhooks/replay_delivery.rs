@@ -0,0 +1,29 @@
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+
+use crate::{
+    api::errors::ApiError,
+    services::webhook::{WebhookDeliverySerializable, WebhookService},
+};
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/{id}/replay",
+    tag = "webhooks",
+    params(("id" = u32, Path)),
+    responses(
+        (status = 202, body = WebhookDeliverySerializable),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn replay_delivery(
+    id: Path<u32>,
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    let delivery = webhook_service.replay(id.into_inner()).await?;
+
+    Ok(HttpResponse::Accepted().json(delivery))
+}
@@ -0,0 +1,44 @@
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpResponse,
+};
+use validator::Validate;
+
+use crate::{
+    api::errors::ApiError,
+    services::webhook::{WebhookSerializable, WebhookService},
+};
+
+use super::dto::UpdateWebhookDto;
+
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    tag = "webhooks",
+    params(("id" = u32, Path)),
+    request_body = UpdateWebhookDto,
+    responses(
+        (status = 200, body = WebhookSerializable),
+        (status = 400, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer_auth" = [])),
+)]
+pub(super) async fn update_webhook(
+    id: Path<u32>,
+    body: Json<UpdateWebhookDto>,
+    webhook_service: Data<WebhookService>,
+) -> Result<HttpResponse, ApiError> {
+    body.validate()?;
+
+    let webhook = webhook_service
+        .update(
+            id.into_inner(),
+            body.url.as_deref(),
+            body.event,
+            body.active,
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
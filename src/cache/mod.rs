@@ -9,6 +9,7 @@ pub enum CacheError<T> {
     ExpireSet,
     GetPair,
     Remove,
+    Increment,
 }
 
 pub struct Cache {
@@ -51,7 +52,7 @@ impl Cache {
             redis::cmd("SET")
                 .arg(key)
                 .arg(value)
-                .query(conn)
+                .query::<()>(conn)
                 .map_err(|err| {
                     log::error!("{:?}", err);
                     CacheError::AddPair
@@ -59,7 +60,7 @@ impl Cache {
             redis::cmd("EXPIREAT")
                 .arg(key)
                 .arg(ttl)
-                .query(conn)
+                .query::<()>(conn)
                 .map_err(|err| {
                     log::error!("{:?}", err);
                     CacheError::ExpireSet
@@ -69,6 +70,31 @@ impl Cache {
         })
     }
 
+    /// Sets the pair only if the key doesn't exist yet, expiring it after `seconds`.
+    /// Returns `false` when the key is already taken.
+    pub fn add_pair_if_absent(
+        &self,
+        key: &str,
+        value: &str,
+        seconds: usize,
+    ) -> Result<bool, CacheError<CacheError<()>>> {
+        self.apply(|conn| {
+            let result: Option<String> = redis::cmd("SET")
+                .arg(key)
+                .arg(value)
+                .arg("NX")
+                .arg("EX")
+                .arg(seconds)
+                .query(conn)
+                .map_err(|err| {
+                    log::error!("{:?}", err);
+                    CacheError::AddPair
+                })?;
+
+            Ok(result.is_some())
+        })
+    }
+
     pub fn get_pair(&self, key: &str) -> Result<Option<String>, CacheError<CacheError<()>>> {
         self.apply(|conn| {
             let value: Option<String> = redis::cmd("GET").arg(key).query(conn).map_err(|err| {
@@ -80,6 +106,34 @@ impl Cache {
         })
     }
 
+    /// Increments the counter under `key`. A new counter expires after `seconds`,
+    /// without them it's kept until removed.
+    pub fn increment(
+        &self,
+        key: &str,
+        seconds: Option<usize>,
+    ) -> Result<u64, CacheError<CacheError<()>>> {
+        self.apply(|conn| {
+            let value: u64 = redis::cmd("INCR").arg(key).query(conn).map_err(|err| {
+                log::error!("{:?}", err);
+                CacheError::Increment
+            })?;
+
+            if let (1, Some(seconds)) = (value, seconds) {
+                redis::cmd("EXPIRE")
+                    .arg(key)
+                    .arg(seconds)
+                    .query::<()>(conn)
+                    .map_err(|err| {
+                        log::error!("{:?}", err);
+                        CacheError::ExpireSet
+                    })?;
+            }
+
+            Ok(value)
+        })
+    }
+
     pub fn remove(&self, key: &str) -> Result<(), CacheError<CacheError<()>>> {
         self.apply(|conn| {
             let _: Option<i32> = redis::cmd("DEL").arg(key).query(conn).map_err(|err| {
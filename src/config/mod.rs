@@ -1,18 +1,45 @@
 use std::env;
 
 use crate::db::DbUrlProvider;
+use crate::services::antispam::AntiSpamConfigProvider;
 use crate::services::auth::{SaltProvider, SecretsProvider};
-use crate::services::files::UploadPathProvider;
+use crate::services::files::{file_type::FileType, UploadPathProvider, UploadPurpose};
+use crate::services::invoice::InvoiceConfigProvider;
+use crate::services::notification::NotifierConfigProvider;
+use crate::services::phone::PhoneConfigProvider;
 
 pub struct Config {
     db_url: String,
     host: String,
     port: u16,
+    #[allow(dead_code)]
     salt: String,
     jwt_secret_access: String,
     jwt_secret_refresh: String,
     redis_url: String,
     upload_path: String,
+    thumbnail_widths: Vec<u32>,
+    image_file_types: Vec<FileType>,
+    document_file_types: Vec<FileType>,
+    notifier: String,
+    smtp_url: Option<String>,
+    mail_from: String,
+    manager_email: Option<String>,
+    notifications_path: String,
+    shop_name: String,
+    shop_address: String,
+    shop_phone: String,
+    shop_email: String,
+    shop_tax_id: Option<String>,
+    invoice_font_path: Option<String>,
+    order_rate_limit_ip: u64,
+    order_rate_limit_phone: u64,
+    order_rate_window: usize,
+    human_verifier: String,
+    pow_difficulty: u32,
+    local_verifier_token: Option<String>,
+    trust_proxy: bool,
+    phone_region: String,
 }
 
 impl Config {
@@ -54,6 +81,101 @@ impl UploadPathProvider for Config {
     fn upload_path(&self) -> &str {
         &self.upload_path
     }
+
+    fn thumbnail_widths(&self) -> &[u32] {
+        &self.thumbnail_widths
+    }
+
+    fn allowed_file_types(&self, purpose: UploadPurpose) -> &[FileType] {
+        match purpose {
+            UploadPurpose::Image => &self.image_file_types,
+            UploadPurpose::Document => &self.document_file_types,
+        }
+    }
+}
+
+impl NotifierConfigProvider for Config {
+    fn notifier(&self) -> &str {
+        &self.notifier
+    }
+
+    fn smtp_url(&self) -> Option<&str> {
+        self.smtp_url.as_deref()
+    }
+
+    fn mail_from(&self) -> &str {
+        &self.mail_from
+    }
+
+    fn manager_email(&self) -> Option<&str> {
+        self.manager_email.as_deref()
+    }
+
+    fn notifications_path(&self) -> &str {
+        &self.notifications_path
+    }
+}
+
+impl InvoiceConfigProvider for Config {
+    fn shop_name(&self) -> &str {
+        &self.shop_name
+    }
+
+    fn shop_address(&self) -> &str {
+        &self.shop_address
+    }
+
+    fn shop_phone(&self) -> &str {
+        &self.shop_phone
+    }
+
+    fn shop_email(&self) -> &str {
+        &self.shop_email
+    }
+
+    fn shop_tax_id(&self) -> Option<&str> {
+        self.shop_tax_id.as_deref()
+    }
+
+    fn invoice_font_path(&self) -> Option<&str> {
+        self.invoice_font_path.as_deref()
+    }
+}
+
+impl AntiSpamConfigProvider for Config {
+    fn order_rate_limit_ip(&self) -> u64 {
+        self.order_rate_limit_ip
+    }
+
+    fn order_rate_limit_phone(&self) -> u64 {
+        self.order_rate_limit_phone
+    }
+
+    fn order_rate_window(&self) -> usize {
+        self.order_rate_window
+    }
+
+    fn human_verifier(&self) -> &str {
+        &self.human_verifier
+    }
+
+    fn pow_difficulty(&self) -> u32 {
+        self.pow_difficulty
+    }
+
+    fn local_verifier_token(&self) -> Option<&str> {
+        self.local_verifier_token.as_deref()
+    }
+
+    fn trust_proxy(&self) -> bool {
+        self.trust_proxy
+    }
+}
+
+impl PhoneConfigProvider for Config {
+    fn phone_region(&self) -> &str {
+        &self.phone_region
+    }
 }
 
 impl Default for Config {
@@ -84,7 +206,75 @@ impl Default for Config {
                 log::warn!("UPLOAD_PATH not specified. Default file path: ./uploads");
 
                 "./uploads".to_string()
-            })
+            }),
+            thumbnail_widths: env::var("THUMBNAIL_WIDTHS")
+                .unwrap_or("320,640,1280".into())
+                .split(',')
+                .filter_map(|width| width.trim().parse().ok())
+                .collect(),
+            image_file_types: file_types(
+                env::var("IMAGE_FILE_TYPES").unwrap_or("png,jpeg,webp,gif,avif".into()),
+            ),
+            document_file_types: file_types(
+                env::var("DOCUMENT_FILE_TYPES").unwrap_or("pdf".into()),
+            ),
+            notifier: env::var("NOTIFIER").unwrap_or_else(|_| {
+                log::warn!("NOTIFIER not specified. Notifications will be written to the log");
+
+                "log".to_string()
+            }),
+            smtp_url: env::var("SMTP_URL").ok(),
+            mail_from: env::var("MAIL_FROM").unwrap_or("noreply@localhost".into()),
+            manager_email: env::var("MANAGER_EMAIL").ok(),
+            notifications_path: env::var("NOTIFICATIONS_PATH").unwrap_or("./notifications".into()),
+            shop_name: env::var("SHOP_NAME").unwrap_or_else(|_| {
+                log::warn!("SHOP_NAME not specified. Invoices will be issued without shop details");
+
+                "".to_string()
+            }),
+            shop_address: env::var("SHOP_ADDRESS").unwrap_or_default(),
+            shop_phone: env::var("SHOP_PHONE").unwrap_or_default(),
+            shop_email: env::var("SHOP_EMAIL").unwrap_or_default(),
+            shop_tax_id: env::var("SHOP_TAX_ID").ok(),
+            invoice_font_path: env::var("INVOICE_FONT_PATH").ok(),
+            order_rate_limit_ip: env::var("ORDER_RATE_LIMIT_IP")
+                .map(|e| e.parse().unwrap_or(5))
+                .unwrap_or(5),
+            order_rate_limit_phone: env::var("ORDER_RATE_LIMIT_PHONE")
+                .map(|e| e.parse().unwrap_or(3))
+                .unwrap_or(3),
+            order_rate_window: env::var("ORDER_RATE_WINDOW")
+                .map(|e| e.parse().unwrap_or(3600))
+                .unwrap_or(3600),
+            human_verifier: env::var("HUMAN_VERIFIER").unwrap_or_else(|_| {
+                log::warn!("HUMAN_VERIFIER not specified. Orders won't be checked for bots");
+
+                "none".to_string()
+            }),
+            pow_difficulty: env::var("POW_DIFFICULTY")
+                .map(|e| e.parse().unwrap_or(16))
+                .unwrap_or(16),
+            local_verifier_token: env::var("LOCAL_VERIFIER_TOKEN").ok(),
+            trust_proxy: env::var("TRUST_PROXY")
+                .map(|e| e == "true" || e == "1")
+                .unwrap_or(false),
+            phone_region: env::var("PHONE_REGION").unwrap_or("RU".into()),
         }
     }
 }
+
+/// Comma separated extensions, unknown ones are skipped.
+fn file_types(extensions: String) -> Vec<FileType> {
+    extensions
+        .split(',')
+        .filter_map(|extension| {
+            let file_type = FileType::from_extension(extension.trim());
+
+            if file_type.is_none() {
+                log::warn!("Unknown file type {extension} in the allowed upload types");
+            }
+
+            file_type
+        })
+        .collect()
+}
@@ -13,8 +13,18 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::file_variant::Entity")]
+    FileVariant,
     #[sea_orm(has_many = "super::product::Entity")]
     Product,
+    #[sea_orm(has_many = "super::product_image::Entity")]
+    ProductImage,
+}
+
+impl Related<super::file_variant::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FileVariant.def()
+    }
 }
 
 impl Related<super::product::Entity> for Entity {
@@ -23,4 +33,10 @@ impl Related<super::product::Entity> for Entity {
     }
 }
 
+impl Related<super::product_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductImage.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -8,8 +8,14 @@ pub mod category_product;
 pub mod field;
 pub mod field_product;
 pub mod file;
+pub mod file_variant;
+pub mod invoice;
 pub mod order;
+pub mod order_status_history;
 pub mod product;
+pub mod product_image;
 pub mod products_in_order;
 pub mod sea_orm_active_enums;
 pub mod service;
+pub mod webhook;
+pub mod webhook_delivery;
@@ -9,6 +9,7 @@ pub mod field;
 pub mod field_product;
 pub mod file;
 pub mod order;
+pub mod order_status_history;
 pub mod product;
 pub mod products_in_order;
 pub mod service;
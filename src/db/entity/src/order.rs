@@ -11,14 +11,33 @@ pub struct Model {
     pub surname: String,
     pub phone: String,
     pub address: String,
+    pub status: i32,
+    pub created_at: DateTimeWithTimeZone,
+    pub email: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_one = "super::invoice::Entity")]
+    Invoice,
+    #[sea_orm(has_many = "super::order_status_history::Entity")]
+    OrderStatusHistory,
     #[sea_orm(has_many = "super::products_in_order::Entity")]
     ProductsInOrder,
 }
 
+impl Related<super::invoice::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Invoice.def()
+    }
+}
+
+impl Related<super::order_status_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OrderStatusHistory.def()
+    }
+}
+
 impl Related<super::products_in_order::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ProductsInOrder.def()
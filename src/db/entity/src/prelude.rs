@@ -6,7 +6,13 @@ pub use super::category_product::Entity as CategoryProduct;
 pub use super::field::Entity as Field;
 pub use super::field_product::Entity as FieldProduct;
 pub use super::file::Entity as File;
+pub use super::file_variant::Entity as FileVariant;
+pub use super::invoice::Entity as Invoice;
 pub use super::order::Entity as Order;
+pub use super::order_status_history::Entity as OrderStatusHistory;
 pub use super::product::Entity as Product;
+pub use super::product_image::Entity as ProductImage;
 pub use super::products_in_order::Entity as ProductsInOrder;
 pub use super::service::Entity as Service;
+pub use super::webhook::Entity as Webhook;
+pub use super::webhook_delivery::Entity as WebhookDelivery;
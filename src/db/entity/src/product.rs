@@ -12,10 +12,20 @@ pub struct Model {
     pub article: String,
     pub description: String,
     pub photo: Option<Uuid>,
+    pub stock: Option<i32>,
+    pub parent_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_update = "Cascade",
+        on_delete = "Restrict"
+    )]
+    SelfRef,
     #[sea_orm(has_many = "super::category_product::Entity")]
     CategoryProduct,
     #[sea_orm(has_many = "super::field_product::Entity")]
@@ -28,6 +38,8 @@ pub enum Relation {
         on_delete = "SetNull"
     )]
     File,
+    #[sea_orm(has_many = "super::product_image::Entity")]
+    ProductImage,
     #[sea_orm(has_many = "super::products_in_order::Entity")]
     ProductsInOrder,
 }
@@ -50,6 +62,12 @@ impl Related<super::file::Entity> for Entity {
     }
 }
 
+impl Related<super::product_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProductImage.def()
+    }
+}
+
 impl Related<super::products_in_order::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ProductsInOrder.def()
@@ -5,11 +5,14 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "products_in_order")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false)]
-    pub product_id: i32,
-    #[sea_orm(primary_key, auto_increment = false)]
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub product_id: Option<i32>,
     pub order_id: i32,
     pub quantity: i32,
+    pub name: String,
+    pub article: String,
+    pub price: Decimal,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -27,7 +30,7 @@ pub enum Relation {
         from = "Column::ProductId",
         to = "super::product::Column::Id",
         on_update = "Cascade",
-        on_delete = "Cascade"
+        on_delete = "SetNull"
     )]
     Product,
 }
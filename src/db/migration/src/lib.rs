@@ -19,6 +19,20 @@ mod m20240713_174747_stable_category_product_relationships;
 mod m20240713_175628_stable_field_product_relationships;
 mod m20240714_000136_add_quantity_to_products_in_order;
 mod m20240727_214204_alter_field_type;
+mod m20240801_120000_add_order_status;
+mod m20240805_090000_add_price_snapshot_to_products_in_order;
+mod m20240810_150000_add_created_at_to_order;
+mod m20240815_100000_add_stock_to_product;
+mod m20240820_110000_add_email_to_order;
+mod m20240825_120000_add_webhook_tables;
+mod m20240901_100000_add_invoice_table;
+mod m20240905_100000_normalize_order_phones;
+mod m20240910_100000_add_product_search_vector;
+mod m20240915_100000_add_trigram_indexes;
+mod m20240920_100000_add_product_variants;
+mod m20240925_100000_add_product_image_table;
+mod m20240930_100000_add_file_variant_table;
+mod m20241005_100000_keep_lines_of_deleted_products;
 
 pub struct Migrator;
 
@@ -45,6 +59,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20240713_175628_stable_field_product_relationships::Migration),
             Box::new(m20240714_000136_add_quantity_to_products_in_order::Migration),
             Box::new(m20240727_214204_alter_field_type::Migration),
+            Box::new(m20240801_120000_add_order_status::Migration),
+            Box::new(m20240805_090000_add_price_snapshot_to_products_in_order::Migration),
+            Box::new(m20240810_150000_add_created_at_to_order::Migration),
+            Box::new(m20240815_100000_add_stock_to_product::Migration),
+            Box::new(m20240820_110000_add_email_to_order::Migration),
+            Box::new(m20240825_120000_add_webhook_tables::Migration),
+            Box::new(m20240901_100000_add_invoice_table::Migration),
+            Box::new(m20240905_100000_normalize_order_phones::Migration),
+            Box::new(m20240910_100000_add_product_search_vector::Migration),
+            Box::new(m20240915_100000_add_trigram_indexes::Migration),
+            Box::new(m20240920_100000_add_product_variants::Migration),
+            Box::new(m20240925_100000_add_product_image_table::Migration),
+            Box::new(m20240930_100000_add_file_variant_table::Migration),
+            Box::new(m20241005_100000_keep_lines_of_deleted_products::Migration),
         ]
     }
 }
@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .add_column(
+                        ColumnDef::new(Order::Status)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrderStatusHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OrderStatusHistory::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OrderStatusHistory::OrderId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OrderStatusHistory::FromStatus).integer())
+                    .col(
+                        ColumnDef::new(OrderStatusHistory::ToStatus)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OrderStatusHistory::AdminId).integer())
+                    .col(
+                        ColumnDef::new(OrderStatusHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(OrderStatusHistory::Table, OrderStatusHistory::OrderId)
+                            .to(Order::Table, Order::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(OrderStatusHistory::Table, OrderStatusHistory::AdminId)
+                            .to(Admin::Table, Admin::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OrderStatusHistory::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Order::Table)
+                    .drop_column(Order::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+    Id,
+    Status,
+}
+
+#[derive(DeriveIden)]
+enum OrderStatusHistory {
+    Table,
+    Id,
+    OrderId,
+    FromStatus,
+    ToStatus,
+    AdminId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Admin {
+    Table,
+    Id,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductsInOrder::Table)
+                    .add_column(ColumnDef::new(ProductsInOrder::Name).string())
+                    .add_column(ColumnDef::new(ProductsInOrder::Article).string())
+                    .add_column(ColumnDef::new(ProductsInOrder::Price).decimal())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Orders placed before this migration never had a snapshot,
+        // so the current product data is the best we have.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"UPDATE "products_in_order" AS "pio"
+                SET "name" = "product"."name",
+                    "article" = "product"."article",
+                    "price" = "product"."price"
+                FROM "product"
+                WHERE "product"."id" = "pio"."product_id""#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductsInOrder::Table)
+                    .modify_column(ColumnDef::new(ProductsInOrder::Name).string().not_null())
+                    .modify_column(ColumnDef::new(ProductsInOrder::Article).string().not_null())
+                    .modify_column(ColumnDef::new(ProductsInOrder::Price).decimal().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProductsInOrder::Table)
+                    .drop_column(ProductsInOrder::Name)
+                    .drop_column(ProductsInOrder::Article)
+                    .drop_column(ProductsInOrder::Price)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProductsInOrder {
+    Table,
+    Name,
+    Article,
+    Price,
+}
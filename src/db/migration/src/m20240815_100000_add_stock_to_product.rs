@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // NULL stock means the product isn't tracked and can always be ordered
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .add_column(
+                        ColumnDef::new(Product::Stock)
+                            .integer()
+                            .check(Expr::col(Product::Stock).gte(0)),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .drop_column(Product::Stock)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Stock,
+}
@@ -0,0 +1,151 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Webhook::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Webhook::Url).string().not_null())
+                    .col(ColumnDef::new(Webhook::Event).integer().not_null())
+                    .col(ColumnDef::new(Webhook::Secret).string().not_null())
+                    .col(
+                        ColumnDef::new(Webhook::Active)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(Webhook::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_event")
+                    .table(Webhook::Table)
+                    .col(Webhook::Event)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::WebhookId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::Event).integer().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Payload)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Status)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::ResponseStatus).integer())
+                    .col(ColumnDef::new(WebhookDelivery::Error).text())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::LastAttemptAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(WebhookDelivery::Table, WebhookDelivery::WebhookId)
+                            .to(Webhook::Table, Webhook::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_webhook_delivery_webhook_id")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Webhook {
+    Table,
+    Id,
+    Url,
+    Event,
+    Secret,
+    Active,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    WebhookId,
+    Event,
+    Payload,
+    Status,
+    Attempts,
+    ResponseStatus,
+    Error,
+    CreatedAt,
+    LastAttemptAt,
+}
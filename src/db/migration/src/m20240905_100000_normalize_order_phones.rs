@@ -0,0 +1,68 @@
+use phonenumber::{country, Mode};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+
+        // Same region the application uses for numbers without a country code.
+        let region = std::env::var("PHONE_REGION")
+            .ok()
+            .and_then(|region| region.to_uppercase().parse().ok())
+            .unwrap_or(country::Id::RU);
+
+        let rows = db
+            .query_all(
+                builder.build(
+                    Query::select()
+                        .columns([Order::Id, Order::Phone])
+                        .from(Order::Table),
+                ),
+            )
+            .await?;
+
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let phone: String = row.try_get("", "phone")?;
+
+            let normalized = match phonenumber::parse(Some(region), &phone) {
+                Ok(number) if number.is_valid() => number.format().mode(Mode::E164).to_string(),
+                // Left for a manager to fix by hand, the order is still worth keeping.
+                _ => continue,
+            };
+
+            if normalized == phone {
+                continue;
+            }
+
+            db.execute(
+                builder.build(
+                    Query::update()
+                        .table(Order::Table)
+                        .value(Order::Phone, normalized)
+                        .and_where(Expr::col(Order::Id).eq(id)),
+                ),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // The original spelling of the numbers isn't kept, E.164 is valid input anyway.
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Order {
+    Table,
+    Id,
+    Phone,
+}
@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const INDEX_NAME: &str = "idx_product_search_vector";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .add_column(
+                        ColumnDef::new(Product::SearchVector).custom(Alias::new("tsvector")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Field values live in another table, so the vector is kept up to date
+        // by triggers instead of being a generated column.
+        // Name and article weigh the most, then field values, then the description.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"CREATE FUNCTION "product_search_vector"("id" integer, "name" text, "article" text, "description" text)
+                RETURNS tsvector AS $$
+                    SELECT setweight(to_tsvector('russian', coalesce("name", '')), 'A')
+                        || setweight(to_tsvector('russian', coalesce("article", '')), 'A')
+                        || setweight(to_tsvector('russian', coalesce((
+                            SELECT string_agg("value", ' ') FROM "field_product"
+                            WHERE "field_product"."product_id" = $1
+                        ), '')), 'B')
+                        || setweight(to_tsvector('russian', coalesce("description", '')), 'C')
+                $$ LANGUAGE sql STABLE;
+
+                CREATE FUNCTION "product_search_vector_on_product"() RETURNS trigger AS $$
+                BEGIN
+                    NEW."search_vector" := "product_search_vector"(NEW."id", NEW."name", NEW."article", NEW."description");
+                    RETURN NEW;
+                END
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER "product_search_vector" BEFORE INSERT OR UPDATE OF "name", "article", "description"
+                ON "product" FOR EACH ROW EXECUTE FUNCTION "product_search_vector_on_product"();
+
+                CREATE FUNCTION "product_search_vector_on_field_product"() RETURNS trigger AS $$
+                DECLARE
+                    "changed_product_id" integer;
+                BEGIN
+                    IF TG_OP = 'DELETE' THEN
+                        "changed_product_id" := OLD."product_id";
+                    ELSE
+                        "changed_product_id" := NEW."product_id";
+                    END IF;
+
+                    UPDATE "product"
+                    SET "search_vector" = "product_search_vector"("id", "name", "article", "description")
+                    WHERE "id" = "changed_product_id";
+
+                    RETURN NULL;
+                END
+                $$ LANGUAGE plpgsql;
+
+                CREATE TRIGGER "product_search_vector" AFTER INSERT OR UPDATE OR DELETE
+                ON "field_product" FOR EACH ROW EXECUTE FUNCTION "product_search_vector_on_field_product"();
+
+                UPDATE "product"
+                SET "search_vector" = "product_search_vector"("id", "name", "article", "description");"#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(Product::Table)
+                    .col(Product::SearchVector)
+                    .index_type(IndexType::FullText)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"DROP TRIGGER "product_search_vector" ON "field_product";
+                DROP FUNCTION "product_search_vector_on_field_product"();
+                DROP TRIGGER "product_search_vector" ON "product";
+                DROP FUNCTION "product_search_vector_on_product"();
+                DROP FUNCTION "product_search_vector"(integer, text, text, text);"#,
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(Product::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .drop_column(Product::SearchVector)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    SearchVector,
+}
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Trigram indexes back the word similarity operators used by suggestions.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"CREATE EXTENSION IF NOT EXISTS "pg_trgm";
+
+                CREATE INDEX "idx_product_name_trgm" ON "product" USING gin ("name" gin_trgm_ops);
+                CREATE INDEX "idx_product_article_trgm" ON "product" USING gin ("article" gin_trgm_ops);
+                CREATE INDEX "idx_category_name_trgm" ON "category" USING gin ("name" gin_trgm_ops);"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"DROP INDEX "idx_category_name_trgm";
+                DROP INDEX "idx_product_article_trgm";
+                DROP INDEX "idx_product_name_trgm";"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
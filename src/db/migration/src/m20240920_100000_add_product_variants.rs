@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const FOREIGN_KEY_NAME: &str = "fk_product_product";
+const INDEX_NAME: &str = "idx_product_parent_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Variants are products with a parent, the parent holds the shared
+        // name, description and categories. A parent is only deleted after
+        // or together with its variants.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .add_column(ColumnDef::new(Product::ParentId).integer().null())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name(FOREIGN_KEY_NAME)
+                            .from_tbl(Product::Table)
+                            .from_col(Product::ParentId)
+                            .to_tbl(Product::Table)
+                            .to_col(Product::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(Product::Table)
+                    .col(Product::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(Product::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Product::Table)
+                    .drop_foreign_key(Alias::new(FOREIGN_KEY_NAME))
+                    .drop_column(Product::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Id,
+    ParentId,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProductImage::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(ProductImage::ProductId).integer().not_null())
+                    .col(ColumnDef::new(ProductImage::FileId).uuid().not_null())
+                    .col(ColumnDef::new(ProductImage::Position).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(ProductImage::ProductId)
+                            .col(ProductImage::FileId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ProductImage::Table, ProductImage::ProductId)
+                            .to(Product::Table, Product::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(ProductImage::Table, ProductImage::FileId)
+                            .to(File::Table, File::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The single photo becomes the main image of the gallery.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"INSERT INTO "product_image" ("product_id", "file_id", "position")
+                SELECT "id", "photo", 0 FROM "product" WHERE "photo" IS NOT NULL"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProductImage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProductImage {
+    Table,
+    ProductId,
+    FileId,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum Product {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
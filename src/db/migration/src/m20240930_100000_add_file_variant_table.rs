@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FileVariant::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(FileVariant::FileId).uuid().not_null())
+                    .col(ColumnDef::new(FileVariant::Width).integer().not_null())
+                    .col(ColumnDef::new(FileVariant::Filename).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(FileVariant::FileId)
+                            .col(FileVariant::Width),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(FileVariant::Table, FileVariant::FileId)
+                            .to(File::Table, File::Id)
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FileVariant::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum FileVariant {
+    Table,
+    FileId,
+    Width,
+    Filename,
+}
+
+#[derive(DeriveIden)]
+enum File {
+    Table,
+    Id,
+}
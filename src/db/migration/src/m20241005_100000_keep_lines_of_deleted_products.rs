@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Lines carry a snapshot of what was ordered, so they outlive the
+        // product. Without its product a line needs a key of its own.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE "products_in_order"
+                DROP CONSTRAINT "products_in_order_pkey",
+                DROP CONSTRAINT "products_in_order_product_id_fkey",
+                ADD COLUMN "id" SERIAL PRIMARY KEY,
+                ALTER COLUMN "product_id" DROP NOT NULL,
+                ADD CONSTRAINT "products_in_order_order_id_product_id_key"
+                    UNIQUE ("order_id", "product_id"),
+                ADD CONSTRAINT "products_in_order_product_id_fkey"
+                    FOREIGN KEY ("product_id") REFERENCES "product" ("id")
+                    ON UPDATE CASCADE ON DELETE SET NULL"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Lines of deleted products can't be keyed by the product anymore.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"DELETE FROM "products_in_order" WHERE "product_id" IS NULL;
+                ALTER TABLE "products_in_order"
+                DROP CONSTRAINT "products_in_order_product_id_fkey",
+                DROP CONSTRAINT "products_in_order_order_id_product_id_key",
+                DROP COLUMN "id",
+                ALTER COLUMN "product_id" SET NOT NULL,
+                ADD PRIMARY KEY ("product_id", "order_id"),
+                ADD CONSTRAINT "products_in_order_product_id_fkey"
+                    FOREIGN KEY ("product_id") REFERENCES "product" ("id")
+                    ON UPDATE CASCADE ON DELETE CASCADE"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
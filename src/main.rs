@@ -8,7 +8,8 @@ mod utilities;
 use dotenvy::dotenv;
 
 use actix_cors::Cors;
-use actix_web::{error, http::header, middleware::Logger, web, App, HttpServer};
+use actix_multipart::form::MultipartFormConfig;
+use actix_web::{http::header, middleware::Logger, web, App, HttpServer};
 use api::errors::ApiError;
 use cache::Cache;
 use config::Config;
@@ -20,8 +21,9 @@ use services::{company_services::CompanyServicesService, field::FieldService};
 use crate::{
     db::DbUrlProvider,
     services::{
-        auth::AuthService, category::CategoryService, files::FilesService, order::OrderService,
-        product::ProductService,
+        antispam::AntiSpamService, auth::AuthService, category::CategoryService,
+        files::FilesService, invoice::InvoiceService, notification::NotificationService,
+        order::OrderService, phone::PhoneService, product::ProductService, webhook::WebhookService,
     },
 };
 
@@ -52,6 +54,13 @@ async fn main() -> std::io::Result<()> {
     let order_service = web::Data::new(OrderService::new(db.clone()));
     let field_service = web::Data::new(FieldService::new(db.clone()));
     let company_services_service = web::Data::new(CompanyServicesService::new(db.clone()));
+    let webhook_service = web::Data::new(WebhookService::new(db.clone()));
+    let invoice_service = web::Data::new(InvoiceService::new(db.clone(), config.as_ref()));
+    let phone_service = web::Data::new(PhoneService::from_config(config.as_ref()));
+    let antispam_service = web::Data::new(AntiSpamService::from_config(config.as_ref()));
+    let notification_service = web::Data::new(
+        NotificationService::from_config(config.as_ref()).expect("Notifier instance error"),
+    );
 
     log::info!("Running migrations...");
     Migrator::up(&db, None)
@@ -59,13 +68,20 @@ async fn main() -> std::io::Result<()> {
         .expect("Error running migrations");
     log::info!("Migrations successfully applied!");
 
+    if webhook_service.resume_pending().await.is_err() {
+        log::error!("Pending webhook deliveries were not resumed");
+    }
+
     let json_cfg = web::JsonConfig::default()
         .limit(4096)
         .error_handler(|err, _req| {
             log::error!("{:?}", err);
-            error::InternalError::from_response(err, ApiError::invalid_data()).into()
+            ApiError::json_payload(&err).into()
         });
 
+    // Room for a batch of photos, every one of them is limited on its own.
+    let multipart_cfg = MultipartFormConfig::default().total_limit(FilesService::MAX_UPLOAD_SIZE);
+
     log::info!("Starting server at {}:{}", config.host(), config.port());
 
     HttpServer::new(move || {
@@ -78,6 +94,7 @@ async fn main() -> std::io::Result<()> {
                 header::CONTENT_TYPE,
                 header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
                 header::CONTENT_TYPE,
+                header::HeaderName::from_static("idempotency-key"),
             ])
             .supports_credentials()
             .max_age(3600);
@@ -85,6 +102,7 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(cors)
             .app_data(json_cfg.clone())
+            .app_data(multipart_cfg.clone())
             .app_data(config.clone())
             .app_data(cache_data.clone())
             .app_data(product_service.clone())
@@ -94,7 +112,13 @@ async fn main() -> std::io::Result<()> {
             .app_data(order_service.clone())
             .app_data(field_service.clone())
             .app_data(company_services_service.clone())
+            .app_data(notification_service.clone())
+            .app_data(webhook_service.clone())
+            .app_data(invoice_service.clone())
+            .app_data(antispam_service.clone())
+            .app_data(phone_service.clone())
             .wrap(Logger::default())
+            .service(api::docs())
             .service(web::scope("/api").configure(api::configure(config.clone())))
     })
     .bind((host, port))?
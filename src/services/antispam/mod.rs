@@ -0,0 +1,194 @@
+mod verifier;
+
+use std::sync::Arc;
+
+use actix_web::dev::ConnectionInfo;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::cache::Cache;
+
+pub use verifier::{HumanVerifier, LocalVerifier, NoopVerifier, ProofOfWorkVerifier};
+
+#[derive(Copy, Clone, Debug)]
+pub enum AntiSpamErr {
+    Internal,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpamReason {
+    Honeypot,
+    Verification,
+    IpRateLimit,
+    PhoneRateLimit,
+}
+
+impl SpamReason {
+    pub const ALL: [SpamReason; 4] = [
+        SpamReason::Honeypot,
+        SpamReason::Verification,
+        SpamReason::IpRateLimit,
+        SpamReason::PhoneRateLimit,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpamReason::Honeypot => "honeypot",
+            SpamReason::Verification => "verification",
+            SpamReason::IpRateLimit => "ip_rate_limit",
+            SpamReason::PhoneRateLimit => "phone_rate_limit",
+        }
+    }
+}
+
+pub trait AntiSpamConfigProvider {
+    fn order_rate_limit_ip(&self) -> u64;
+    fn order_rate_limit_phone(&self) -> u64;
+    fn order_rate_window(&self) -> usize;
+    fn human_verifier(&self) -> &str;
+    fn pow_difficulty(&self) -> u32;
+    fn local_verifier_token(&self) -> Option<&str>;
+    fn trust_proxy(&self) -> bool;
+}
+
+/// What the anti-spam checks need to know about a submitted order.
+pub struct OrderSubmission<'a> {
+    pub ip: Option<&'a str>,
+    /// Normalized to E.164, proof of work stamps are bound to it.
+    pub phone: &'a str,
+    pub honeypot: &'a str,
+    pub token: Option<&'a str>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct SpamStat {
+    reason: &'static str,
+    rejected: u64,
+}
+
+pub struct AntiSpamService {
+    verifier: Arc<dyn HumanVerifier>,
+    ip_limit: u64,
+    phone_limit: u64,
+    window: usize,
+    trust_proxy: bool,
+}
+
+impl AntiSpamService {
+    pub fn from_config<T>(config: &T) -> Self
+    where
+        T: AntiSpamConfigProvider,
+    {
+        let verifier: Arc<dyn HumanVerifier> = match config.human_verifier() {
+            "pow" => Arc::new(ProofOfWorkVerifier::new(config.pow_difficulty())),
+            "local" => Arc::new(LocalVerifier::new(
+                config
+                    .local_verifier_token()
+                    .expect("LOCAL_VERIFIER_TOKEN must be set"),
+            )),
+            _ => Arc::new(NoopVerifier),
+        };
+
+        Self {
+            verifier,
+            ip_limit: config.order_rate_limit_ip(),
+            phone_limit: config.order_rate_limit_phone(),
+            window: config.order_rate_window(),
+            trust_proxy: config.trust_proxy(),
+        }
+    }
+
+    /// Address of the client. Forwarding headers are only honoured behind
+    /// a trusted proxy, otherwise anyone could pick their own address.
+    pub fn client_ip(&self, info: &ConnectionInfo) -> Option<String> {
+        let addr = if self.trust_proxy {
+            info.realip_remote_addr()
+        } else {
+            info.peer_addr()
+        };
+
+        addr.map(ToOwned::to_owned)
+    }
+
+    /// Rejects the submission if it looks automated. Every rejection is
+    /// logged and counted. Cache failures let the order through.
+    pub fn check(&self, cache: &Cache, submission: &OrderSubmission) -> Result<(), SpamReason> {
+        let result = self.evaluate(cache, submission);
+
+        if let Err(reason) = result {
+            // The phone stays out of the logs, it's personal data.
+            log::warn!(
+                "Order from {} rejected as spam: {}",
+                submission.ip.unwrap_or("unknown"),
+                reason.as_str()
+            );
+
+            if let Err(err) = cache.increment(&Self::counter_key(reason), None) {
+                log::error!("Spam counter not updated: {:?}", err);
+            }
+        }
+
+        result
+    }
+
+    /// Number of rejections per reason since the counters were created.
+    pub fn stats(&self, cache: &Cache) -> Result<Vec<SpamStat>, AntiSpamErr> {
+        SpamReason::ALL
+            .iter()
+            .map(|reason| {
+                let rejected = cache
+                    .get_pair(&Self::counter_key(*reason))
+                    .map_err(|_| AntiSpamErr::Internal)?
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or_default();
+
+                Ok(SpamStat {
+                    reason: reason.as_str(),
+                    rejected,
+                })
+            })
+            .collect()
+    }
+
+    fn evaluate(&self, cache: &Cache, submission: &OrderSubmission) -> Result<(), SpamReason> {
+        // Humans don't see the field, bots tend to fill every input.
+        if !submission.honeypot.is_empty() {
+            return Err(SpamReason::Honeypot);
+        }
+
+        if !self
+            .verifier
+            .verify(cache, submission.token, submission.phone)
+        {
+            return Err(SpamReason::Verification);
+        }
+
+        if let Some(ip) = submission.ip {
+            if self.exceeds(cache, &format!("antispam:ip:{ip}"), self.ip_limit) {
+                return Err(SpamReason::IpRateLimit);
+            }
+        }
+
+        let phone_key = format!("antispam:phone:{}", submission.phone);
+
+        if self.exceeds(cache, &phone_key, self.phone_limit) {
+            return Err(SpamReason::PhoneRateLimit);
+        }
+
+        Ok(())
+    }
+
+    fn exceeds(&self, cache: &Cache, key: &str, limit: u64) -> bool {
+        match cache.increment(key, Some(self.window)) {
+            Ok(count) => count > limit,
+            Err(err) => {
+                log::error!("Rate limit skipped: {:?}", err);
+                false
+            }
+        }
+    }
+
+    fn counter_key(reason: SpamReason) -> String {
+        format!("antispam:rejected:{}", reason.as_str())
+    }
+}
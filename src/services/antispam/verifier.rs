@@ -0,0 +1,187 @@
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+
+use crate::cache::Cache;
+
+/// Tells humans from bots using a token sent along with the form,
+/// e.g. a captcha response or a proof of work stamp.
+pub trait HumanVerifier: Send + Sync {
+    /// `resource` is the value the token must be bound to, the phone for orders.
+    fn verify(&self, cache: &Cache, token: Option<&str>, resource: &str) -> bool;
+}
+
+/// Accepts every request. Used when verification is turned off.
+pub struct NoopVerifier;
+
+impl HumanVerifier for NoopVerifier {
+    fn verify(&self, _cache: &Cache, _token: Option<&str>, _resource: &str) -> bool {
+        true
+    }
+}
+
+/// Accepts only the configured token. For tests and local development.
+pub struct LocalVerifier {
+    token: String,
+}
+
+impl LocalVerifier {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_owned(),
+        }
+    }
+}
+
+impl HumanVerifier for LocalVerifier {
+    fn verify(&self, _cache: &Cache, token: Option<&str>, _resource: &str) -> bool {
+        token == Some(self.token.as_str())
+    }
+}
+
+/// Hashcash-like proof of work. The token is `{unix timestamp}:{nonce}` and
+/// `sha256("{timestamp}:{resource}:{nonce}")` must start with `difficulty`
+/// zero bits. Stamps older than `max_age` seconds are rejected, and so is
+/// every use of a stamp after the first one.
+pub struct ProofOfWorkVerifier {
+    difficulty: u32,
+    max_age: i64,
+}
+
+impl ProofOfWorkVerifier {
+    pub const DEFAULT_MAX_AGE: i64 = 10 * 60;
+
+    pub fn new(difficulty: u32) -> Self {
+        Self {
+            difficulty,
+            max_age: Self::DEFAULT_MAX_AGE,
+        }
+    }
+
+    fn leading_zero_bits(hash: &[u8]) -> u32 {
+        let mut bits = 0;
+
+        for byte in hash {
+            if *byte == 0 {
+                bits += 8;
+                continue;
+            }
+
+            bits += byte.leading_zeros();
+            break;
+        }
+
+        bits
+    }
+}
+
+impl HumanVerifier for ProofOfWorkVerifier {
+    fn verify(&self, cache: &Cache, token: Option<&str>, resource: &str) -> bool {
+        let Some((timestamp, nonce)) = token.and_then(|token| token.split_once(':')) else {
+            return false;
+        };
+
+        let Ok(issued_at) = timestamp.parse::<i64>() else {
+            return false;
+        };
+
+        // The timestamp comes from the client, a difference could overflow.
+        if Utc::now().timestamp().abs_diff(issued_at) > self.max_age as u64 {
+            return false;
+        }
+
+        let hash = Sha256::digest(format!("{timestamp}:{resource}:{nonce}"));
+
+        if Self::leading_zero_bits(&hash) < self.difficulty {
+            return false;
+        }
+
+        // Remembered until the stamp is outdated anyway. Like the rate
+        // limits, a cache failure lets the stamp through.
+        let expires_in = (issued_at + self.max_age - Utc::now().timestamp()).max(1);
+        let key = format!("antispam:stamp:{}", hex::encode(hash));
+
+        match cache.add_pair_if_absent(&key, "", expires_in as usize) {
+            Ok(first_use) => first_use,
+            Err(err) => {
+                log::error!("Stamp not recorded: {:?}", err);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sha2::{Digest, Sha256};
+    use uuid::Uuid;
+
+    use super::{HumanVerifier, LocalVerifier, ProofOfWorkVerifier};
+    use crate::{cache::Cache, utilities::test_utils::test_cache};
+
+    fn mine(timestamp: i64, resource: &str, difficulty: u32) -> String {
+        (0u64..)
+            .map(|nonce| format!("{timestamp}:{nonce}"))
+            .find(|token| {
+                let (timestamp, nonce) = token.split_once(':').unwrap();
+                let hash = Sha256::digest(format!("{timestamp}:{resource}:{nonce}"));
+
+                ProofOfWorkVerifier::leading_zero_bits(&hash) >= difficulty
+            })
+            .unwrap()
+    }
+
+    /// Nothing listens there, so stamps are checked without being recorded.
+    fn unreachable_cache() -> Cache {
+        Cache::new("redis://127.0.0.1:1").unwrap()
+    }
+
+    #[test]
+    fn accepts_mined_stamp_for_the_same_resource_only() {
+        let cache = unreachable_cache();
+        let verifier = ProofOfWorkVerifier::new(8);
+        let token = mine(Utc::now().timestamp(), "+79120000000", 8);
+
+        assert!(verifier.verify(&cache, Some(&token), "+79120000000"));
+        assert!(!verifier.verify(&cache, Some(&token), "+79120000001"));
+        assert!(!ProofOfWorkVerifier::new(24).verify(&cache, Some(&token), "+79120000000"));
+        assert!(!verifier.verify(&cache, Some("not-a-stamp"), "+79120000000"));
+        assert!(!verifier.verify(&cache, None, "+79120000000"));
+    }
+
+    #[test]
+    fn rejects_outdated_stamp() {
+        let cache = unreachable_cache();
+        let verifier = ProofOfWorkVerifier::new(4);
+        let issued_at = Utc::now().timestamp() - ProofOfWorkVerifier::DEFAULT_MAX_AGE - 1;
+        let token = mine(issued_at, "+79120000000", 4);
+        let overflowing = format!("{}:0", i64::MIN);
+
+        assert!(!verifier.verify(&cache, Some(&token), "+79120000000"));
+        assert!(!verifier.verify(&cache, Some(&overflowing), "+79120000000"));
+    }
+
+    #[test]
+    fn rejects_replayed_stamp() {
+        let Some(cache) = test_cache() else {
+            return;
+        };
+        let verifier = ProofOfWorkVerifier::new(4);
+        // Every run mines a stamp of its own.
+        let resource = Uuid::new_v4().to_string();
+        let token = mine(Utc::now().timestamp(), &resource, 4);
+
+        assert!(verifier.verify(&cache, Some(&token), &resource));
+        assert!(!verifier.verify(&cache, Some(&token), &resource));
+    }
+
+    #[test]
+    fn local_verifier_matches_configured_token() {
+        let cache = unreachable_cache();
+        let verifier = LocalVerifier::new("test-token");
+
+        assert!(verifier.verify(&cache, Some("test-token"), "+79120000000"));
+        assert!(!verifier.verify(&cache, Some("other"), "+79120000000"));
+        assert!(!verifier.verify(&cache, None, "+79120000000"));
+    }
+}
@@ -26,6 +26,7 @@ pub struct JwtAccessData {
     pub exp: usize,
 }
 
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize)]
 struct JwtRefreshData {
     uid: Uuid,
@@ -36,6 +37,7 @@ pub struct AuthService {
     db: DatabaseConnection,
 }
 
+#[allow(dead_code)]
 pub trait SaltProvider {
     fn salt(&self) -> &[u8];
 }
@@ -5,14 +5,22 @@ use sea_orm::{
     Set,
 };
 use serde::Serialize;
+use utoipa::ToSchema;
 
 use entity::category::{self, Entity as Category};
 use entity::product::{self, Entity as Product};
 
-use crate::{services::product::ProductSerializable, utilities::serde_utils::Patch};
+use crate::{
+    services::{
+        product::ProductSerializable,
+        webhook::{webhook_event::WebhookEvent, WebhookService},
+    },
+    utilities::serde_utils::Patch,
+};
 
 pub struct CategoryService {
     db: DatabaseConnection,
+    webhooks: WebhookService,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -23,14 +31,14 @@ pub enum CategoriesServiceErr {
     NotFound,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct CategorySerializable {
     id: u32,
     name: String,
     parent_id: Option<u32>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct CategoryWithProductsSerializable {
     id: u32,
     name: String,
@@ -38,19 +46,20 @@ pub struct CategoryWithProductsSerializable {
     products: Vec<ProductSerializable>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct CategoryInsertion {
     id: u32,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct CategoryTreeSerializable {
     id: u32,
     name: String,
+    #[schema(value_type = Vec<CategoryTreeSerializable>, no_recursion)]
     categories: Rc<RefCell<Vec<CategoryTreeSerializable>>>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct CategoriesIdx {
     idx: Vec<u32>,
 }
@@ -163,7 +172,10 @@ impl From<&category::Model> for CategoryTreeSerializable {
 
 impl CategoryService {
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            webhooks: WebhookService::new(db.clone()),
+            db,
+        }
     }
 
     pub async fn all(&self) -> Result<Vec<CategorySerializable>, CategoriesServiceErr> {
@@ -213,7 +225,7 @@ impl CategoryService {
             ..Default::default()
         };
 
-        Category::insert(category)
+        let insertion = Category::insert(category)
             .exec(&self.db)
             .await
             .map(|model| CategoryInsertion {
@@ -237,7 +249,18 @@ impl CategoryService {
                     CategoriesServiceErr::Internal
                 }
                 _ => CategoriesServiceErr::Internal,
-            })
+            })?;
+
+        self.webhooks.dispatch(
+            WebhookEvent::CategoryCreated,
+            &CategorySerializable {
+                id: insertion.id,
+                name: name.to_owned(),
+                parent_id,
+            },
+        );
+
+        Ok(insertion)
     }
 
     pub async fn update(
@@ -271,7 +294,7 @@ impl CategoryService {
             category.parent_id = Set(Some(parent_id as i32));
         }
 
-        category
+        let category: CategorySerializable = category
             .save(&self.db)
             .await
             .map(Into::into)
@@ -293,7 +316,12 @@ impl CategoryService {
                     CategoriesServiceErr::Internal
                 }
                 _ => CategoriesServiceErr::Internal,
-            })
+            })?;
+
+        self.webhooks
+            .dispatch(WebhookEvent::CategoryUpdated, &category);
+
+        Ok(category)
     }
 
     pub async fn delete(&self, idx: &[u32]) -> Result<CategoriesIdx, CategoriesServiceErr> {
@@ -309,7 +337,13 @@ impl CategoryService {
             .filter(category::Column::Id.is_in(values))
             .exec(&self.db)
             .await
-            .map(|_| categories.into())
-            .map_err(|_| CategoriesServiceErr::Internal)
+            .map_err(|_| CategoriesServiceErr::Internal)?;
+
+        let deleted: CategoriesIdx = categories.into();
+
+        self.webhooks
+            .dispatch(WebhookEvent::CategoryDeleted, &deleted);
+
+        Ok(deleted)
     }
 }
@@ -1,14 +1,15 @@
 use rust_decimal::Decimal;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct CompanyServiceSerializable {
     pub id: u32,
     pub name: String,
     pub price: Decimal,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct CompanyServiceIdSerializable {
     pub id: u32,
 }
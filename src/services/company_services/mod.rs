@@ -58,7 +58,6 @@ impl CompanyServicesService {
             id: Set(id as i32),
             name: Set(name.to_string()),
             price: Set(price),
-            ..Default::default()
         };
         service::Entity::update(model)
             .exec(&self.db)
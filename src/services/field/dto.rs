@@ -1,15 +1,16 @@
 use super::field_type::FieldType;
 use entity::field::Model as FieldModel;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct FieldSerializable {
     pub id: i32,
     pub name: String,
     pub r#type: FieldType,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct FieldId {
     pub(super) id: u32,
 }
@@ -32,6 +33,7 @@ pub enum FieldGetRemoveError {
     Unknown,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Debug, Serialize)]
 pub enum FieldUpdateError {
     #[serde(rename = "not_found")]
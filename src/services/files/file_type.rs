@@ -0,0 +1,124 @@
+use mime::Mime;
+
+/// Type of an uploaded file, detected from its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Png,
+    Jpeg,
+    Webp,
+    Gif,
+    Avif,
+    Pdf,
+}
+
+impl FileType {
+    /// Enough to reach the compatible brands of an AVIF `ftyp` box.
+    pub const HEADER_LEN: usize = 64;
+
+    const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    const JPEG_SIGNATURE: [u8; 3] = [0xFF, 0xD8, 0xFF];
+
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&Self::PNG_SIGNATURE) {
+            Some(Self::Png)
+        } else if header.starts_with(&Self::JPEG_SIGNATURE) {
+            Some(Self::Jpeg)
+        } else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+            Some(Self::Webp)
+        } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if is_avif(header) {
+            Some(Self::Avif)
+        } else if header.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else {
+            None
+        }
+    }
+
+    /// Type of a stored file by its extension, `jpg` is what older uploads kept.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            "gif" => Some(Self::Gif),
+            "avif" => Some(Self::Avif),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+            Self::Gif => "gif",
+            Self::Avif => "avif",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    pub fn mime(self) -> Mime {
+        match self {
+            Self::Png => mime::IMAGE_PNG,
+            Self::Jpeg => mime::IMAGE_JPEG,
+            Self::Gif => mime::IMAGE_GIF,
+            Self::Pdf => mime::APPLICATION_PDF,
+            Self::Webp => "image/webp".parse().expect("valid mime"),
+            Self::Avif => "image/avif".parse().expect("valid mime"),
+        }
+    }
+
+    /// Whether thumbnails can be made, GIFs would lose their animation.
+    pub fn is_resizable(self) -> bool {
+        matches!(self, Self::Png | Self::Jpeg | Self::Webp)
+    }
+}
+
+/// ISO media file with `avif` or `avis` as its major or one of its compatible brands.
+fn is_avif(header: &[u8]) -> bool {
+    if header.len() < 16 || &header[4..8] != b"ftyp" {
+        return false;
+    }
+
+    let box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let is_avif_brand = |brand: &[u8]| brand == b"avif" || brand == b"avis";
+
+    is_avif_brand(&header[8..12])
+        || header[16..box_size.clamp(16, header.len())]
+            .chunks_exact(4)
+            .any(is_avif_brand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileType;
+
+    #[test]
+    fn detects_by_signature() {
+        let webp = b"RIFF\x24\x00\x00\x00WEBPVP8 ";
+        let gif = b"GIF89a\x01\x00\x01\x00";
+        let pdf = b"%PDF-1.7\n";
+
+        assert_eq!(FileType::detect(b"\xFF\xD8\xFF\xE0"), Some(FileType::Jpeg));
+        assert_eq!(FileType::detect(webp), Some(FileType::Webp));
+        assert_eq!(FileType::detect(gif), Some(FileType::Gif));
+        assert_eq!(FileType::detect(pdf), Some(FileType::Pdf));
+        assert_eq!(FileType::detect(b"RIFF\x24\x00\x00\x00WAVE"), None);
+        assert_eq!(FileType::detect(b"<svg"), None);
+        assert_eq!(FileType::detect(b""), None);
+    }
+
+    #[test]
+    fn detects_avif_by_brand() {
+        let avif = b"\x00\x00\x00\x1cftypavif\x00\x00\x00\x00avifmif1miaf";
+        let compatible = b"\x00\x00\x00\x1cftypmif1\x00\x00\x00\x00mif1avifmiaf";
+        let heic = b"\x00\x00\x00\x18ftypheic\x00\x00\x00\x00mif1heic";
+
+        assert_eq!(FileType::detect(avif), Some(FileType::Avif));
+        assert_eq!(FileType::detect(compatible), Some(FileType::Avif));
+        assert_eq!(FileType::detect(heic), None);
+    }
+}
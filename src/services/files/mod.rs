@@ -1,11 +1,22 @@
-use std::{fs, io::Read, path::Path};
+pub mod file_type;
+mod thumbnails;
+
+use std::{fs, io::Read, path::{Path, PathBuf}};
 
 use actix_multipart::form::tempfile::TempFile;
-use sea_orm::{DatabaseConnection, EntityTrait, Set};
-use serde::Serialize;
+use actix_web::rt::task;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use entity::file::{self, Entity as File};
+use entity::file_variant::{self, Entity as FileVariant};
+
+use file_type::FileType;
+use thumbnails::Thumbnail;
 
 pub struct FilesService {
     db: DatabaseConnection
@@ -20,7 +31,7 @@ pub enum FilesServiceErr {
     MaxFileSizeExceed
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FileName {
     file: String,
 }
@@ -33,42 +44,60 @@ impl From<file::Model> for FileName {
     }
 }
 
+/// Outcome of one file of an upload, `name` is the name the client sent it with.
+pub struct UploadResult {
+    pub name: Option<String>,
+    pub result: Result<FileName, FilesServiceErr>,
+}
+
+/// What a file is uploaded for, every purpose accepts its own file types.
+#[derive(Deserialize, Debug, Clone, Copy, Default, ToSchema)]
+pub enum UploadPurpose {
+    #[default]
+    #[serde(rename = "image")]
+    Image,
+    #[serde(rename = "document")]
+    Document,
+}
+
 pub trait UploadPathProvider {
     fn upload_path(&self) -> &str;
+    fn thumbnail_widths(&self) -> &[u32];
+    fn allowed_file_types(&self, purpose: UploadPurpose) -> &[FileType];
 }
 
 impl FilesService {
     const MAX_FILE_SIZE: usize = 5_242_880;
-    const PNG_FILE_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
-    const JPG_FILE_SIGNATURE: [u8; 3] = [0xFF, 0xD8, 0xFF];
+    pub const MAX_UPLOAD_SIZE: usize = 25 * Self::MAX_FILE_SIZE;
 
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
     }
 
-    fn has_valid_signature(&self, file: &mut TempFile) -> Result<bool, FilesServiceErr> {
-        let mut buf: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 0];
-        
-        file.file.read(&mut buf)
+    /// The type comes from the contents only, the name and `Content-Type` sent
+    /// by the client are not trusted.
+    fn detect_file_type(&self, file: &mut TempFile) -> Result<Option<FileType>, FilesServiceErr> {
+        let mut header = Vec::with_capacity(FileType::HEADER_LEN);
+
+        file.file.as_file_mut()
+            .take(FileType::HEADER_LEN as u64)
+            .read_to_end(&mut header)
             .map_err(|err| {
                 log::error!("{:?}", err);
                 FilesServiceErr::Internal
             })?;
 
-        log::info!("{:?}", buf);
-
-        Ok(self.is_png(&buf) || self.is_jpeg(&buf[..3]))
-    }
-
-    fn is_png(&self, buf: &[u8; 8]) -> bool {
-        buf == &FilesService::PNG_FILE_SIGNATURE
+        Ok(FileType::detect(&header))
     }
 
-    fn is_jpeg(&self, buf: &[u8]) -> bool {
-        buf == FilesService::JPG_FILE_SIGNATURE
-    }
-
-    pub async fn get_file<T>(&self, uid: Uuid, config: &T) -> Result<String, FilesServiceErr>
+    /// Path and type of the file, or of its narrowest thumbnail that is at least `width` wide.
+    /// The original is served when every thumbnail is narrower.
+    pub async fn get_file<T>(
+        &self,
+        uid: Uuid,
+        width: Option<u32>,
+        config: &T
+    ) -> Result<(String, Option<FileType>), FilesServiceErr>
         where
             T: UploadPathProvider,
     {
@@ -81,57 +110,315 @@ impl FilesService {
 
         let db_file = db_file.unwrap();
 
+        let thumbnail = match width {
+            Some(width) => FileVariant::find()
+                .filter(file_variant::Column::FileId.eq(uid))
+                .filter(file_variant::Column::Width.gte(width as i32))
+                .order_by_asc(file_variant::Column::Width)
+                .one(&self.db)
+                .await
+                .map_err(|_| FilesServiceErr::Internal)?,
+            None => None,
+        };
+
+        let filename = thumbnail.map_or(db_file.filename, |thumbnail| thumbnail.filename);
         let directory = config.upload_path();
-        let path = Path::new(&directory).join(db_file.filename);
+        let path = Path::new(&directory).join(filename);
+        let file_type = path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(FileType::from_extension);
 
-        path.into_os_string().into_string().map_err(|_| FilesServiceErr::Internal)
+        path.into_os_string().into_string()
+            .map(|path| (path, file_type))
+            .map_err(|_| FilesServiceErr::Internal)
     }
 
-    pub async fn save_file<T>(
+    /// Every file is checked and saved on its own, a rejected one doesn't stop the rest.
+    pub async fn save_files<T>(
         &self,
         files: Vec<TempFile>,
+        purpose: UploadPurpose,
         config: &T
-    ) -> Result<FileName, FilesServiceErr> 
+    ) -> Result<Vec<UploadResult>, FilesServiceErr>
     where
         T: UploadPathProvider
     {
+        if files.is_empty() {
+            return Err(FilesServiceErr::NoFilesToUpload)
+        }
 
-        let mut f = files.into_iter().nth(0).ok_or(FilesServiceErr::NoFilesToUpload)?;
+        let mut results = Vec::with_capacity(files.len());
 
+        for mut f in files {
+            let name = f.file_name.take();
+            let result = self.save_file(f, purpose, config).await;
+
+            results.push(UploadResult { name, result });
+        }
+
+        Ok(results)
+    }
+
+    async fn save_file<T>(
+        &self,
+        mut f: TempFile,
+        purpose: UploadPurpose,
+        config: &T
+    ) -> Result<FileName, FilesServiceErr> 
+    where
+        T: UploadPathProvider
+    {
         if f.size > FilesService::MAX_FILE_SIZE {
             return Err(FilesServiceErr::MaxFileSizeExceed)
         } 
 
         log::warn!("{:?}", f.content_type);
 
-        if !self.has_valid_signature(&mut f)? {
-            return Err(FilesServiceErr::ForbiddenFileType)
-        }
-
-        let full_filename = f.file_name.ok_or(FilesServiceErr::ForbiddenFileType)?;
-        let ext = full_filename.split('.').last().ok_or(FilesServiceErr::ForbiddenFileType)?;
+        let file_type = self.detect_file_type(&mut f)?
+            .filter(|file_type| config.allowed_file_types(purpose).contains(file_type))
+            .ok_or(FilesServiceErr::ForbiddenFileType)?;
 
         let uuid = Uuid::new_v4();
-        let filename = format!("{uuid}.{ext}");
+        let filename = format!("{uuid}.{}", file_type.extension());
         let directory = config.upload_path();
         let path = Path::new(&directory).join(&filename);
 
-        fs::copy(f.file.path(), path)
+        fs::copy(f.file.path(), &path)
             .map_err(|err| {
                 log::error!("{:?}", err);
                 FilesServiceErr::Internal
             })?;
 
+        let thumbnails = if file_type.is_resizable() {
+            self.make_thumbnails(path.clone(), uuid, config.thumbnail_widths()).await
+        } else {
+            Vec::new()
+        };
+
+        let copies: Vec<PathBuf> = thumbnails.iter()
+            .map(|thumbnail| Path::new(&directory).join(&thumbnail.filename))
+            .chain([path])
+            .collect();
+
+        if let Err(err) = self.insert_file(uuid, filename, thumbnails).await {
+            log::error!("{:?}", err);
+
+            // Without its rows nothing would ever serve or remove the copies.
+            for copy in copies {
+                if let Err(err) = fs::remove_file(&copy) {
+                    log::error!("{} not removed: {:?}", copy.display(), err);
+                }
+            }
+
+            return Err(FilesServiceErr::Internal)
+        }
+
+        Ok(FileName { file: uuid.to_string() })
+    }
+
+    async fn insert_file(
+        &self,
+        uuid: Uuid,
+        filename: String,
+        thumbnails: Vec<Thumbnail>
+    ) -> Result<(), sea_orm::DbErr> {
         let file_data = file::ActiveModel {
             id: Set(uuid),
             filename: Set(filename),
             ..Default::default()
         };
 
+        let transaction = self.db.begin().await?;
+
         File::insert(file_data)
-            .exec(&self.db)
+            .exec(&transaction)
+            .await?;
+
+        if !thumbnails.is_empty() {
+            let variants = thumbnails.into_iter().map(|thumbnail| file_variant::ActiveModel {
+                file_id: Set(uuid),
+                width: Set(thumbnail.width as i32),
+                filename: Set(thumbnail.filename),
+            });
+
+            FileVariant::insert_many(variants)
+                .exec(&transaction)
+                .await?;
+        }
+
+        transaction.commit().await
+    }
+
+    /// Thumbnails are only an optimization, the original is still served
+    /// when an image can't be decoded or resized.
+    async fn make_thumbnails(&self, original: PathBuf, uuid: Uuid, widths: &[u32]) -> Vec<Thumbnail> {
+        let widths = widths.to_vec();
+        let resized = task::spawn_blocking(move || {
+            thumbnails::make_thumbnails(&original, &uuid.to_string(), &widths)
+        })
+        .await;
+
+        match resized {
+            Ok(Ok(thumbnails)) => thumbnails,
+            Ok(Err(err)) => {
+                log::error!("Thumbnails of {} not created: {:?}", uuid, err);
+                Vec::new()
+            }
+            Err(err) => {
+                log::error!("{:?}", err);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Cursor};
+
+    use actix_multipart::form::tempfile::TempFile;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use sea_orm::{ConnectionTrait, EntityTrait, PaginatorTrait};
+    use tempfile::{NamedTempFile, TempDir};
+
+    use entity::file::Entity as File;
+
+    use super::file_type::FileType;
+    use super::{FilesService, FilesServiceErr, UploadPathProvider, UploadPurpose};
+    use crate::utilities::test_utils::TestDb;
+
+    struct Uploads {
+        directory: TempDir,
+        path: String,
+    }
+
+    impl Uploads {
+        fn new() -> Self {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().to_str().unwrap().to_owned();
+
+            Self { directory, path }
+        }
+
+        fn stored(&self) -> usize {
+            fs::read_dir(self.directory.path()).unwrap().count()
+        }
+    }
+
+    impl UploadPathProvider for Uploads {
+        fn upload_path(&self) -> &str {
+            &self.path
+        }
+
+        fn thumbnail_widths(&self) -> &[u32] {
+            &[32]
+        }
+
+        fn allowed_file_types(&self, _: UploadPurpose) -> &[FileType] {
+            &[FileType::Png, FileType::Jpeg]
+        }
+    }
+
+    /// Part of a multipart upload as actix leaves it on disk.
+    fn part(name: &str, contents: &[u8]) -> TempFile {
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), contents).unwrap();
+
+        TempFile {
+            file,
+            content_type: None,
+            file_name: Some(name.to_owned()),
+            size: contents.len(),
+        }
+    }
+
+    fn png() -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        DynamicImage::ImageRgb8(RgbImage::new(64, 64))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+
+        bytes
+    }
+
+    #[actix_web::test]
+    async fn rejected_parts_dont_stop_the_rest() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = FilesService::new(test_db.db.clone());
+        let uploads = Uploads::new();
+        let mut oversized = part("huge.png", &png());
+        oversized.size = FilesService::MAX_FILE_SIZE + 1;
+
+        let results = service
+            .save_files(
+                vec![
+                    part("photo.png", &png()),
+                    part("notes.txt", b"notes"),
+                    oversized,
+                ],
+                UploadPurpose::Image,
+                &uploads,
+            )
             .await
-            .map(|model| FileName { file: model.last_insert_id.to_string() })
-            .map_err(|_| FilesServiceErr::Internal)
+            .unwrap();
+
+        let names: Vec<_> = results
+            .iter()
+            .map(|result| result.name.as_deref())
+            .collect();
+        assert_eq!(
+            names,
+            [Some("photo.png"), Some("notes.txt"), Some("huge.png")]
+        );
+
+        assert!(results[0].result.is_ok());
+        assert!(matches!(
+            results[1].result,
+            Err(FilesServiceErr::ForbiddenFileType)
+        ));
+        assert!(matches!(
+            results[2].result,
+            Err(FilesServiceErr::MaxFileSizeExceed)
+        ));
+
+        assert_eq!(File::find().count(&test_db.db).await.unwrap(), 1);
+        // The original and its thumbnail.
+        assert_eq!(uploads.stored(), 2);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn removes_copies_of_files_that_werent_recorded() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = FilesService::new(test_db.db.clone());
+        let uploads = Uploads::new();
+
+        // Fails the insert of the thumbnails, after both copies were written.
+        test_db
+            .db
+            .execute_unprepared(r#"DROP TABLE "file_variant""#)
+            .await
+            .unwrap();
+
+        let results = service
+            .save_files(
+                vec![part("photo.png", &png())],
+                UploadPurpose::Image,
+                &uploads,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(results[0].result, Err(FilesServiceErr::Internal)));
+        assert_eq!(File::find().count(&test_db.db).await.unwrap(), 0);
+        assert_eq!(uploads.stored(), 0);
+
+        test_db.drop().await;
     }
 }
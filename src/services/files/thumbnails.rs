@@ -0,0 +1,58 @@
+use std::{fs, io::BufWriter, path::Path};
+
+use image::{
+    codecs::webp::WebPEncoder, imageops::FilterType, DynamicImage, ImageDecoder, ImageError,
+    ImageReader,
+};
+
+/// Resized copy of an uploaded image, stored next to the original.
+pub(super) struct Thumbnail {
+    pub(super) width: u32,
+    pub(super) filename: String,
+}
+
+/// Writes `{stem}_{width}.webp` for every width narrower than the image,
+/// wider ones would only be an upscaled copy of the original.
+///
+/// The `image` crate only encodes lossless WebP. That keeps the build free of
+/// libwebp, at the cost of photo thumbnails several times larger than lossy
+/// ones of the same width. Line art and screenshots don't lose much to it.
+pub(super) fn make_thumbnails(
+    original: &Path,
+    stem: &str,
+    widths: &[u32],
+) -> Result<Vec<Thumbnail>, ImageError> {
+    let mut decoder = ImageReader::open(original)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+
+    // WebP has no EXIF, so photos taken sideways are rotated before resizing.
+    image.apply_orientation(orientation);
+
+    let directory = original.parent().unwrap_or(Path::new("."));
+    let mut thumbnails = Vec::new();
+
+    for &width in widths {
+        if width == 0 || width >= image.width() {
+            continue;
+        }
+
+        let resized = image.resize(width, image.height(), FilterType::Lanczos3);
+        // The encoder only takes 8 bit pixels.
+        let resized = if resized.color().has_alpha() {
+            DynamicImage::ImageRgba8(resized.into_rgba8())
+        } else {
+            DynamicImage::ImageRgb8(resized.into_rgb8())
+        };
+
+        let filename = format!("{stem}_{width}.webp");
+        let writer = BufWriter::new(fs::File::create(directory.join(&filename))?);
+
+        resized.write_with_encoder(WebPEncoder::new_lossless(writer))?;
+        thumbnails.push(Thumbnail { width, filename });
+    }
+
+    Ok(thumbnails)
+}
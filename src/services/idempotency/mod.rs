@@ -0,0 +1,213 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cache::Cache;
+
+#[derive(Copy, Clone, Debug)]
+pub enum IdempotencyErr {
+    InvalidKey,
+    Internal,
+}
+
+#[derive(Debug)]
+pub enum IdempotencyState<T> {
+    /// The key is new, the request should be processed.
+    Started,
+    /// The request was already processed, this is its response.
+    Replay(T),
+    /// The first request with this key hasn't finished yet.
+    InProgress,
+    /// The key was used with a different body.
+    Conflict,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdempotencyRecord<T> {
+    hash: String,
+    response: Option<T>,
+}
+
+/// `Idempotency-Key` of a request, bound to the hash of its body.
+pub struct IdempotencyKey<'a> {
+    cache: &'a Cache,
+    key: String,
+    hash: String,
+}
+
+impl<'a> IdempotencyKey<'a> {
+    pub const MAX_KEY_LENGTH: usize = 255;
+    const TTL_SECONDS: usize = 24 * 60 * 60;
+    /// Lifetime of a reservation, so a request that died midway doesn't
+    /// block retries with its key for a whole day.
+    const IN_PROGRESS_TTL_SECONDS: usize = 60;
+    /// A key that keeps expiring between reserving and reading it is given up on.
+    const MAX_ATTEMPTS: usize = 3;
+
+    /// `scope` separates keys of different endpoints.
+    pub fn new<B>(
+        cache: &'a Cache,
+        scope: &str,
+        key: &str,
+        body: &B,
+    ) -> Result<Self, IdempotencyErr>
+    where
+        B: Serialize,
+    {
+        if key.is_empty()
+            || key.len() > Self::MAX_KEY_LENGTH
+            || !key.chars().all(|c| c.is_ascii_graphic())
+        {
+            return Err(IdempotencyErr::InvalidKey);
+        }
+
+        let body = serde_json::to_vec(body).map_err(|_| IdempotencyErr::Internal)?;
+
+        Ok(Self {
+            cache,
+            key: format!("idempotency:{scope}:{key}"),
+            hash: hex::encode(Sha256::digest(body)),
+        })
+    }
+
+    /// Reserves the key or reports what happened to the request that took it.
+    pub fn begin<T>(&self) -> Result<IdempotencyState<T>, IdempotencyErr>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let pending = serde_json::to_string(&IdempotencyRecord::<T> {
+            hash: self.hash.to_owned(),
+            response: None,
+        })
+        .map_err(|_| IdempotencyErr::Internal)?;
+
+        for _ in 0..Self::MAX_ATTEMPTS {
+            let reserved = self
+                .cache
+                .add_pair_if_absent(&self.key, &pending, Self::IN_PROGRESS_TTL_SECONDS)
+                .map_err(|_| IdempotencyErr::Internal)?;
+
+            if reserved {
+                return Ok(IdempotencyState::Started);
+            }
+
+            let record = self
+                .cache
+                .get_pair(&self.key)
+                .map_err(|_| IdempotencyErr::Internal)?;
+
+            // Expired between the two commands, nobody holds the key anymore.
+            let Some(record) = record else {
+                continue;
+            };
+
+            let record: IdempotencyRecord<T> =
+                serde_json::from_str(&record).map_err(|_| IdempotencyErr::Internal)?;
+
+            if record.hash != self.hash {
+                return Ok(IdempotencyState::Conflict);
+            }
+
+            return Ok(match record.response {
+                Some(response) => IdempotencyState::Replay(response),
+                None => IdempotencyState::InProgress,
+            });
+        }
+
+        log::error!("Idempotency key {} expired on every attempt", self.key);
+
+        Err(IdempotencyErr::Internal)
+    }
+
+    /// Stores the response so that replays of the request get it back.
+    pub fn complete<T>(&self, response: &T)
+    where
+        T: Serialize,
+    {
+        let record = serde_json::to_string(&IdempotencyRecord {
+            hash: self.hash.to_owned(),
+            response: Some(response),
+        });
+
+        if let Err(err) = record {
+            log::error!("{:?}", err);
+            return;
+        }
+
+        let expire_at = chrono::Utc::now().timestamp() as usize + Self::TTL_SECONDS;
+
+        if let Err(err) = self.cache.add_pair(&self.key, &record.unwrap(), expire_at) {
+            log::error!("Idempotency key {} not stored: {:?}", self.key, err);
+        }
+    }
+
+    /// Frees the key after a failed request, so the client can retry with it.
+    pub fn abort(&self) {
+        let _ = self.cache.remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{IdempotencyErr, IdempotencyKey};
+    use crate::utilities::test_utils::test_cache;
+
+    /// State as debug printed, e.g. `Replay(7)`.
+    fn begin(key: &IdempotencyKey) -> String {
+        format!("{:?}", key.begin::<u32>().unwrap())
+    }
+
+    #[test]
+    fn replays_completed_requests_with_the_same_body_only() {
+        let Some(cache) = test_cache() else {
+            return;
+        };
+        let key = Uuid::new_v4().to_string();
+        let body = json!({ "product": 1 });
+        let first = IdempotencyKey::new(&cache, "test", &key, &body).unwrap();
+        let retry = IdempotencyKey::new(&cache, "test", &key, &body).unwrap();
+        let other = IdempotencyKey::new(&cache, "test", &key, &json!({ "product": 2 })).unwrap();
+
+        assert_eq!(begin(&first), "Started");
+        assert_eq!(begin(&retry), "InProgress");
+        assert_eq!(begin(&other), "Conflict");
+
+        first.complete(&7u32);
+
+        assert_eq!(begin(&retry), "Replay(7)");
+        assert_eq!(begin(&other), "Conflict");
+    }
+
+    #[test]
+    fn aborted_requests_free_the_key() {
+        let Some(cache) = test_cache() else {
+            return;
+        };
+        let key = Uuid::new_v4().to_string();
+        let body = json!({ "product": 1 });
+        let first = IdempotencyKey::new(&cache, "test", &key, &body).unwrap();
+
+        assert_eq!(begin(&first), "Started");
+
+        first.abort();
+
+        let retry = IdempotencyKey::new(&cache, "test", &key, &body).unwrap();
+        assert_eq!(begin(&retry), "Started");
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        let Some(cache) = test_cache() else {
+            return;
+        };
+        let long = "k".repeat(IdempotencyKey::MAX_KEY_LENGTH + 1);
+
+        for key in ["", "with space", "ключ", long.as_str()] {
+            let result = IdempotencyKey::new(&cache, "test", key, &());
+
+            assert!(matches!(result, Err(IdempotencyErr::InvalidKey)), "{key}");
+        }
+    }
+}
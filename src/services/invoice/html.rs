@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use super::InvoiceSerializable;
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub(super) fn render(invoice: &InvoiceSerializable) -> String {
+    let shop = &invoice.shop;
+    let order = &invoice.order;
+    let number = invoice.formatted_number();
+    let mut rows = String::new();
+
+    for (index, line) in order.products.iter().enumerate() {
+        let _ = write!(
+            rows,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{:.2}</td><td class=\"num\">{:.2}</td></tr>",
+            index + 1,
+            escape(&line.product.article),
+            escape(&line.product.name),
+            line.quantity,
+            line.product.price,
+            line.total
+        );
+    }
+
+    let tax_id = shop
+        .tax_id
+        .as_deref()
+        .map(|tax_id| format!("<div>Tax ID: {}</div>", escape(tax_id)))
+        .unwrap_or_default();
+    let email = order
+        .email
+        .as_deref()
+        .map(|email| format!("<div>Email: {}</div>", escape(email)))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Invoice {number}</title>
+<style>
+body {{ font-family: sans-serif; font-size: 14px; margin: 40px; }}
+h1 {{ font-size: 20px; margin-bottom: 4px; }}
+table {{ border-collapse: collapse; width: 100%; margin-top: 24px; }}
+th, td {{ border-bottom: 1px solid #ccc; padding: 6px; text-align: left; }}
+.num {{ text-align: right; }}
+.total {{ font-weight: bold; }}
+</style>
+</head>
+<body>
+<header>
+<div><strong>{shop_name}</strong></div>
+<div>{shop_address}</div>
+<div>{shop_phone}</div>
+<div>{shop_email}</div>
+{tax_id}
+</header>
+<h1>Invoice No. {number} of {date}</h1>
+<div>Order #{order_id}</div>
+<section>
+<div>Customer: {name} {surname}</div>
+<div>Phone: {phone}</div>
+{email}
+<div>Address: {address}</div>
+</section>
+<table>
+<thead><tr><th>#</th><th>Article</th><th>Product</th><th class="num">Qty</th><th class="num">Price</th><th class="num">Total</th></tr></thead>
+<tbody>{rows}</tbody>
+<tfoot><tr class="total"><td colspan="5" class="num">Total</td><td class="num">{total:.2}</td></tr></tfoot>
+</table>
+</body>
+</html>
+"#,
+        shop_name = escape(&shop.name),
+        shop_address = escape(&shop.address),
+        shop_phone = escape(&shop.phone),
+        shop_email = escape(&shop.email),
+        date = invoice.created_at.format("%d.%m.%Y"),
+        order_id = order.id,
+        name = escape(&order.name),
+        surname = escape(&order.surname),
+        phone = escape(&order.phone),
+        address = escape(&order.address),
+        total = order.total,
+    )
+}
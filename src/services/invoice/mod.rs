@@ -0,0 +1,280 @@
+mod html;
+mod pdf;
+
+use std::sync::Arc;
+
+use chrono::{DateTime, FixedOffset};
+use entity::invoice::{self, Entity as Invoice};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Statement,
+    TransactionTrait,
+};
+use serde::Serialize;
+
+use crate::services::order::{OrderGetError, OrderSerializable, OrderService};
+
+#[derive(Clone, Debug)]
+pub enum InvoiceErr {
+    Internal,
+    NotFound,
+}
+
+pub trait InvoiceConfigProvider {
+    fn shop_name(&self) -> &str;
+    fn shop_address(&self) -> &str;
+    fn shop_phone(&self) -> &str;
+    fn shop_email(&self) -> &str;
+    fn shop_tax_id(&self) -> Option<&str>;
+    fn invoice_font_path(&self) -> Option<&str>;
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ShopDetails {
+    name: String,
+    address: String,
+    phone: String,
+    email: String,
+    tax_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct InvoiceSerializable {
+    /// Missing on a preview of an invoice that wasn't issued yet.
+    number: Option<u32>,
+    created_at: DateTime<FixedOffset>,
+    shop: ShopDetails,
+    order: OrderSerializable,
+}
+
+impl InvoiceSerializable {
+    /// Number as printed on the document.
+    pub fn formatted_number(&self) -> String {
+        match self.number {
+            Some(number) => format!("{:06}", number),
+            None => "DRAFT".to_owned(),
+        }
+    }
+}
+
+pub struct InvoiceService {
+    db: DatabaseConnection,
+    orders: OrderService,
+    shop: ShopDetails,
+    font: Option<Arc<Vec<u8>>>,
+}
+
+impl InvoiceService {
+    /// Reads the TTF font for PDFs up front. Without it the built-in
+    /// Helvetica is used, which can't render Cyrillic.
+    pub fn new<T>(db: DatabaseConnection, config: &T) -> Self
+    where
+        T: InvoiceConfigProvider,
+    {
+        let font = config
+            .invoice_font_path()
+            .and_then(|path| match std::fs::read(path) {
+                Ok(font) => Some(Arc::new(font)),
+                Err(err) => {
+                    log::error!("Invoice font {} is not readable: {:?}", path, err);
+                    None
+                }
+            });
+
+        Self {
+            orders: OrderService::new(db.clone()),
+            db,
+            shop: ShopDetails {
+                name: config.shop_name().to_owned(),
+                address: config.shop_address().to_owned(),
+                phone: config.shop_phone().to_owned(),
+                email: config.shop_email().to_owned(),
+                tax_id: config.shop_tax_id().map(ToOwned::to_owned),
+            },
+            font,
+        }
+    }
+
+    /// Invoice of the order. The number is taken from the sequence when the
+    /// invoice is issued for the first time and never changes afterwards.
+    pub async fn get(&self, order_id: u32) -> Result<InvoiceSerializable, InvoiceErr> {
+        let order = self.order(order_id).await?;
+        let invoice = self.issue(order_id).await.map_err(|err| {
+            log::error!("{:?}", err);
+            InvoiceErr::Internal
+        })?;
+
+        Ok(self.serializable(order, Some(invoice)))
+    }
+
+    /// Invoice as it is or would be issued, a preview never takes a number.
+    pub async fn preview(&self, order_id: u32) -> Result<InvoiceSerializable, InvoiceErr> {
+        let order = self.order(order_id).await?;
+        let invoice = self.find(order_id).await.map_err(|err| {
+            log::error!("{:?}", err);
+            InvoiceErr::Internal
+        })?;
+
+        Ok(self.serializable(order, invoice))
+    }
+
+    async fn order(&self, order_id: u32) -> Result<OrderSerializable, InvoiceErr> {
+        self.orders.get(order_id).await.map_err(|err| match err {
+            OrderGetError::NotFound => InvoiceErr::NotFound,
+            OrderGetError::Internal => InvoiceErr::Internal,
+        })
+    }
+
+    fn serializable(
+        &self,
+        order: OrderSerializable,
+        invoice: Option<invoice::Model>,
+    ) -> InvoiceSerializable {
+        InvoiceSerializable {
+            number: invoice.as_ref().map(|invoice| invoice.number as u32),
+            created_at: invoice.map_or_else(
+                || chrono::Utc::now().fixed_offset(),
+                |invoice| invoice.created_at,
+            ),
+            shop: self.shop.clone(),
+            order,
+        }
+    }
+
+    /// Issues the invoice, the PDF is the document handed to the customer.
+    pub async fn pdf(&self, order_id: u32) -> Result<Vec<u8>, InvoiceErr> {
+        let invoice = self.get(order_id).await?;
+        let font = self.font.clone();
+
+        actix_web::rt::task::spawn_blocking(move || {
+            pdf::render(&invoice, font.as_ref().map(|font| font.as_slice()))
+        })
+        .await
+        .map_err(|_| InvoiceErr::Internal)?
+    }
+
+    pub async fn html(&self, order_id: u32) -> Result<String, InvoiceErr> {
+        self.preview(order_id)
+            .await
+            .map(|invoice| html::render(&invoice))
+    }
+
+    /// Numbers are gapless: the table is locked while the next one is picked,
+    /// so concurrent requests can't take the same number or skip one.
+    async fn issue(&self, order_id: u32) -> Result<invoice::Model, sea_orm::DbErr> {
+        if let Some(existing) = self.find(order_id).await? {
+            return Ok(existing);
+        }
+
+        let transaction = self.db.begin().await?;
+
+        transaction
+            .execute_unprepared(r#"LOCK TABLE "invoice" IN SHARE ROW EXCLUSIVE MODE"#)
+            .await?;
+
+        transaction
+            .execute(Statement::from_sql_and_values(
+                transaction.get_database_backend(),
+                r#"INSERT INTO "invoice" ("order_id", "number")
+                SELECT $1, COALESCE(MAX("number"), 0) + 1 FROM "invoice"
+                ON CONFLICT ("order_id") DO NOTHING"#,
+                [(order_id as i32).into()],
+            ))
+            .await?;
+
+        let invoice = Invoice::find()
+            .filter(invoice::Column::OrderId.eq(order_id as i32))
+            .one(&transaction)
+            .await?
+            .ok_or(sea_orm::DbErr::RecordNotFound("invoice".to_owned()))?;
+
+        transaction.commit().await?;
+
+        Ok(invoice)
+    }
+
+    async fn find(&self, order_id: u32) -> Result<Option<invoice::Model>, sea_orm::DbErr> {
+        Invoice::find()
+            .filter(invoice::Column::OrderId.eq(order_id as i32))
+            .one(&self.db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::{invoice, order};
+    use sea_orm::{EntityTrait, PaginatorTrait, Set};
+
+    use super::{InvoiceConfigProvider, InvoiceErr, InvoiceService};
+    use crate::utilities::test_utils::TestDb;
+
+    struct Shop;
+
+    impl InvoiceConfigProvider for Shop {
+        fn shop_name(&self) -> &str {
+            "Shop"
+        }
+
+        fn shop_address(&self) -> &str {
+            "Moscow"
+        }
+
+        fn shop_phone(&self) -> &str {
+            "+79120000000"
+        }
+
+        fn shop_email(&self) -> &str {
+            "shop@example.com"
+        }
+
+        fn shop_tax_id(&self) -> Option<&str> {
+            None
+        }
+
+        fn invoice_font_path(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    async fn insert_order(service: &InvoiceService) -> u32 {
+        order::Entity::insert(order::ActiveModel {
+            name: Set("Ivan".to_owned()),
+            surname: Set("Petrov".to_owned()),
+            phone: Set("+79120000000".to_owned()),
+            address: Set("Moscow".to_owned()),
+            ..Default::default()
+        })
+        .exec(&service.db)
+        .await
+        .unwrap()
+        .last_insert_id as u32
+    }
+
+    #[actix_web::test]
+    async fn numbers_are_gapless_and_previews_take_none() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = InvoiceService::new(test_db.db.clone(), &Shop);
+        let first = insert_order(&service).await;
+        let second = insert_order(&service).await;
+
+        let issued = service.get(first).await.unwrap();
+        let again = service.get(first).await.unwrap();
+        assert_eq!(issued.number, Some(1));
+        assert_eq!(again.number, Some(1));
+        assert_eq!(again.created_at, issued.created_at);
+
+        let preview = service.preview(second).await.unwrap();
+        assert_eq!(preview.formatted_number(), "DRAFT");
+        assert_eq!(invoice::Entity::find().count(&test_db.db).await.unwrap(), 1);
+
+        assert_eq!(service.get(second).await.unwrap().number, Some(2));
+        assert_eq!(service.preview(first).await.unwrap().number, Some(1));
+
+        let missing = service.preview(9999).await;
+        assert!(matches!(missing, Err(InvoiceErr::NotFound)));
+
+        test_db.drop().await;
+    }
+}
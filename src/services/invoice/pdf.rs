@@ -0,0 +1,178 @@
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Point,
+};
+
+use super::{InvoiceErr, InvoiceSerializable};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const ROW_HEIGHT: f32 = 7.0;
+
+/// Left edge of each table column: #, article, product, quantity, price, total.
+const COLUMNS: [f32; 6] = [15.0, 25.0, 55.0, 130.0, 147.0, 172.0];
+const PRODUCT_NAME_LIMIT: usize = 40;
+
+struct Cursor {
+    document: PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    y: f32,
+}
+
+impl Cursor {
+    fn text(&self, text: &str, size: f32, x: f32) {
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y), &self.font);
+    }
+
+    fn rule(&self) {
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Moves down, starting a new page when the bottom margin is reached.
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+
+        if self.y < MARGIN {
+            let (page, layer) = self
+                .document
+                .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+
+            self.layer = self.document.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+}
+
+fn truncate(value: &str, limit: usize) -> String {
+    if value.chars().count() <= limit {
+        return value.to_owned();
+    }
+
+    let mut truncated: String = value.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub(super) fn render(
+    invoice: &InvoiceSerializable,
+    font: Option<&[u8]>,
+) -> Result<Vec<u8>, InvoiceErr> {
+    let number = invoice.formatted_number();
+    let (document, page, layer) = PdfDocument::new(
+        format!("Invoice {number}"),
+        Mm(PAGE_WIDTH),
+        Mm(PAGE_HEIGHT),
+        "Layer 1",
+    );
+
+    let font = match font {
+        Some(font) => document.add_external_font(font),
+        None => document.add_builtin_font(BuiltinFont::Helvetica),
+    }
+    .map_err(|err| {
+        log::error!("{:?}", err);
+        InvoiceErr::Internal
+    })?;
+
+    let layer = document.get_page(page).get_layer(layer);
+    let mut cursor = Cursor {
+        document,
+        layer,
+        font,
+        y: PAGE_HEIGHT - MARGIN - 5.0,
+    };
+
+    let shop = &invoice.shop;
+    let order = &invoice.order;
+
+    cursor.text(&shop.name, 16.0, MARGIN);
+    cursor.advance(6.0);
+
+    for line in [&shop.address, &shop.phone, &shop.email] {
+        cursor.text(line, 9.0, MARGIN);
+        cursor.advance(4.5);
+    }
+
+    if let Some(tax_id) = &shop.tax_id {
+        cursor.text(&format!("Tax ID: {tax_id}"), 9.0, MARGIN);
+        cursor.advance(4.5);
+    }
+
+    cursor.advance(8.0);
+    cursor.text(
+        &format!(
+            "Invoice No. {} of {}",
+            number,
+            invoice.created_at.format("%d.%m.%Y")
+        ),
+        14.0,
+        MARGIN,
+    );
+    cursor.advance(6.0);
+    cursor.text(&format!("Order #{}", order.id), 10.0, MARGIN);
+    cursor.advance(10.0);
+
+    cursor.text(
+        &format!("Customer: {} {}", order.name, order.surname),
+        10.0,
+        MARGIN,
+    );
+    cursor.advance(5.0);
+    cursor.text(&format!("Phone: {}", order.phone), 10.0, MARGIN);
+    cursor.advance(5.0);
+
+    if let Some(email) = &order.email {
+        cursor.text(&format!("Email: {email}"), 10.0, MARGIN);
+        cursor.advance(5.0);
+    }
+
+    cursor.text(&format!("Address: {}", order.address), 10.0, MARGIN);
+    cursor.advance(10.0);
+
+    for (header, x) in ["#", "Article", "Product", "Qty", "Price", "Total"]
+        .iter()
+        .zip(COLUMNS)
+    {
+        cursor.text(header, 10.0, x);
+    }
+
+    cursor.advance(2.5);
+    cursor.rule();
+    cursor.advance(ROW_HEIGHT - 2.5);
+
+    for (index, line) in order.products.iter().enumerate() {
+        let cells = [
+            (index + 1).to_string(),
+            truncate(&line.product.article, 14),
+            truncate(&line.product.name, PRODUCT_NAME_LIMIT),
+            line.quantity.to_string(),
+            format!("{:.2}", line.product.price),
+            format!("{:.2}", line.total),
+        ];
+
+        for (cell, x) in cells.iter().zip(COLUMNS) {
+            cursor.text(cell, 9.0, x);
+        }
+
+        cursor.advance(ROW_HEIGHT);
+    }
+
+    cursor.advance(-ROW_HEIGHT + 2.5);
+    cursor.rule();
+    cursor.advance(ROW_HEIGHT);
+    cursor.text(&format!("Total: {:.2}", order.total), 12.0, COLUMNS[4]);
+
+    cursor.document.save_to_bytes().map_err(|err| {
+        log::error!("{:?}", err);
+        InvoiceErr::Internal
+    })
+}
@@ -1,7 +1,13 @@
+pub mod antispam;
 pub mod auth;
 pub mod category;
 pub mod field;
 pub mod files;
+pub mod idempotency;
+pub mod invoice;
+pub mod notification;
 pub mod order;
+pub mod phone;
 pub mod product;
+pub mod webhook;
 pub mod company_services;
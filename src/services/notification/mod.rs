@@ -0,0 +1,134 @@
+mod sinks;
+mod smtp;
+
+use std::{fmt::Write, sync::Arc};
+
+use crate::services::order::OrderSerializable;
+
+pub use sinks::{FileNotifier, LogNotifier};
+pub use smtp::SmtpNotifier;
+
+#[derive(Debug)]
+pub enum NotifierErr {
+    InvalidAddress,
+    Build,
+    Delivery,
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivery channel for notifications. Implementations may block,
+/// `NotificationService` always calls them off the request path.
+pub trait Notifier: Send + Sync {
+    fn send(&self, notification: &Notification) -> Result<(), NotifierErr>;
+}
+
+pub trait NotifierConfigProvider {
+    fn notifier(&self) -> &str;
+    fn smtp_url(&self) -> Option<&str>;
+    fn mail_from(&self) -> &str;
+    fn manager_email(&self) -> Option<&str>;
+    fn notifications_path(&self) -> &str;
+}
+
+pub struct NotificationService {
+    notifier: Arc<dyn Notifier>,
+    manager_email: Option<String>,
+}
+
+impl NotificationService {
+    pub fn new(notifier: Arc<dyn Notifier>, manager_email: Option<String>) -> Self {
+        Self {
+            notifier,
+            manager_email,
+        }
+    }
+
+    pub fn from_config<T>(config: &T) -> Result<Self, NotifierErr>
+    where
+        T: NotifierConfigProvider,
+    {
+        let notifier: Arc<dyn Notifier> = match config.notifier() {
+            "smtp" => {
+                let url = config.smtp_url().ok_or(NotifierErr::Build)?;
+
+                Arc::new(SmtpNotifier::new(url, config.mail_from())?)
+            }
+            "file" => Arc::new(FileNotifier::new(config.notifications_path())),
+            _ => Arc::new(LogNotifier),
+        };
+
+        Ok(Self::new(
+            notifier,
+            config.manager_email().map(ToOwned::to_owned),
+        ))
+    }
+
+    /// Sends the order summary to the shop manager and, if the customer
+    /// left an email, to the customer. Returns immediately.
+    pub fn order_created(&self, order: &OrderSerializable) {
+        let summary = Self::order_summary(order);
+
+        if let Some(manager_email) = &self.manager_email {
+            self.dispatch(Notification {
+                to: manager_email.to_owned(),
+                subject: format!("New order #{}", order.id),
+                body: summary.clone(),
+            });
+        }
+
+        if let Some(email) = &order.email {
+            self.dispatch(Notification {
+                to: email.to_owned(),
+                subject: format!("Your order #{} has been received", order.id),
+                body: format!(
+                    "Hello, {}!\n\nThank you for your order. Our manager will contact you soon.\n\n{}",
+                    order.name, summary
+                ),
+            });
+        }
+    }
+
+    fn dispatch(&self, notification: Notification) {
+        let notifier = self.notifier.clone();
+
+        actix_web::rt::task::spawn_blocking(move || {
+            if let Err(err) = notifier.send(&notification) {
+                log::error!("Notification to {} failed: {:?}", notification.to, err);
+            }
+        });
+    }
+
+    fn order_summary(order: &OrderSerializable) -> String {
+        let mut summary = String::new();
+
+        let _ = writeln!(summary, "Order #{}", order.id);
+        let _ = writeln!(summary, "Customer: {} {}", order.name, order.surname);
+        let _ = writeln!(summary, "Phone: {}", order.phone);
+
+        if let Some(email) = &order.email {
+            let _ = writeln!(summary, "Email: {}", email);
+        }
+
+        let _ = writeln!(summary, "Address: {}", order.address);
+        let _ = writeln!(summary);
+
+        for line in order.products.iter() {
+            let _ = writeln!(
+                summary,
+                "{} ({}) x {} = {}",
+                line.product.name, line.product.article, line.quantity, line.total
+            );
+        }
+
+        let _ = writeln!(summary);
+        let _ = writeln!(summary, "Total: {}", order.total);
+
+        summary
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use super::{Notification, Notifier, NotifierErr};
+
+/// Writes notifications to the application log. Default for local development.
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn send(&self, notification: &Notification) -> Result<(), NotifierErr> {
+        log::info!(
+            "Notification to {}: {}\n{}",
+            notification.to,
+            notification.subject,
+            notification.body
+        );
+
+        Ok(())
+    }
+}
+
+/// Appends notifications to `notifications.log` in the given directory.
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(directory: &str) -> Self {
+        Self {
+            path: PathBuf::from(directory).join("notifications.log"),
+        }
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn send(&self, notification: &Notification) -> Result<(), NotifierErr> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory).map_err(|err| {
+                log::error!("{:?}", err);
+                NotifierErr::Delivery
+            })?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                NotifierErr::Delivery
+            })?;
+
+        writeln!(
+            file,
+            "[{}] To: {}\nSubject: {}\n\n{}\n",
+            chrono::Utc::now().to_rfc3339(),
+            notification.to,
+            notification.subject,
+            notification.body
+        )
+        .map_err(|err| {
+            log::error!("{:?}", err);
+            NotifierErr::Delivery
+        })
+    }
+}
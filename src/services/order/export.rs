@@ -0,0 +1,206 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, FixedOffset};
+use rust_decimal::Decimal;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use super::{order_status::OrderStatus, OrderFilter, OrderGetError, OrderService};
+
+const HEADERS: [&str; 13] = [
+    "order_id",
+    "created_at",
+    "status",
+    "name",
+    "surname",
+    "phone",
+    "email",
+    "address",
+    "article",
+    "product",
+    "quantity",
+    "price",
+    "total",
+];
+
+#[derive(Clone, Debug)]
+pub enum OrderExportErr {
+    Internal,
+}
+
+/// One row of the export, a single product line of an order.
+#[derive(Clone, Debug)]
+pub struct OrderExportLine {
+    order_id: u32,
+    created_at: DateTime<FixedOffset>,
+    status: OrderStatus,
+    name: String,
+    surname: String,
+    phone: String,
+    email: Option<String>,
+    address: String,
+    article: String,
+    product: String,
+    quantity: u32,
+    price: Decimal,
+    total: Decimal,
+}
+
+impl OrderService {
+    /// Number of orders loaded per query while exporting.
+    pub const EXPORT_BATCH_SIZE: usize = 200;
+
+    /// Ids of every order matching the filter, in the requested order.
+    /// Taken once up front so that orders created during a long export
+    /// don't shift the batches.
+    pub async fn export_ids(&self, filter: &OrderFilter) -> Result<Vec<i32>, OrderGetError> {
+        Self::sorted_ids_selector(filter)
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|_| OrderGetError::Internal)
+    }
+
+    pub async fn export_lines(&self, ids: &[i32]) -> Result<Vec<OrderExportLine>, OrderGetError> {
+        let orders = self.orders_by_ids(ids).await?;
+
+        Ok(orders
+            .into_iter()
+            .flat_map(|order| {
+                order.products.into_iter().map(move |line| OrderExportLine {
+                    order_id: order.id,
+                    created_at: order.created_at,
+                    status: order.status,
+                    name: order.name.to_owned(),
+                    surname: order.surname.to_owned(),
+                    phone: order.phone.to_owned(),
+                    email: order.email.to_owned(),
+                    address: order.address.to_owned(),
+                    article: line.product.article,
+                    product: line.product.name,
+                    quantity: line.quantity,
+                    price: line.product.price,
+                    total: line.total,
+                })
+            })
+            .collect())
+    }
+}
+
+impl OrderExportLine {
+    fn cells(&self) -> [Cow<'_, str>; 13] {
+        [
+            self.order_id.to_string().into(),
+            self.created_at.to_rfc3339().into(),
+            self.status.as_str().into(),
+            self.name.as_str().into(),
+            self.surname.as_str().into(),
+            self.phone.as_str().into(),
+            self.email.as_deref().unwrap_or_default().into(),
+            self.address.as_str().into(),
+            self.article.as_str().into(),
+            self.product.as_str().into(),
+            self.quantity.to_string().into(),
+            self.price.to_string().into(),
+            self.total.to_string().into(),
+        ]
+    }
+}
+
+/// Spreadsheets evaluate cells starting with these characters as formulas.
+/// Phone numbers like `+79120000000` are left as they are.
+fn escape_formula(value: Cow<'_, str>) -> Cow<'_, str> {
+    let mut chars = value.chars();
+    let dangerous = match chars.next() {
+        Some('=' | '@' | '\t' | '\r') => true,
+        Some('+' | '-') => !chars.all(|c| c.is_ascii_digit()),
+        _ => false,
+    };
+
+    if dangerous {
+        return format!("'{value}").into();
+    }
+
+    value
+}
+
+/// CSV chunk for a batch of lines. The header, with a BOM so that Excel
+/// detects UTF-8, is written for the first batch only.
+pub fn lines_to_csv(
+    lines: &[OrderExportLine],
+    with_header: bool,
+) -> Result<Vec<u8>, OrderExportErr> {
+    let mut buffer = Vec::new();
+
+    if with_header {
+        buffer.extend_from_slice("\u{feff}".as_bytes());
+    }
+
+    let mut writer = csv::Writer::from_writer(buffer);
+
+    if with_header {
+        writer.write_record(HEADERS).map_err(|err| {
+            log::error!("{:?}", err);
+            OrderExportErr::Internal
+        })?;
+    }
+
+    for line in lines {
+        writer
+            .write_record(
+                line.cells()
+                    .map(escape_formula)
+                    .iter()
+                    .map(AsRef::<str>::as_ref),
+            )
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                OrderExportErr::Internal
+            })?;
+    }
+
+    writer.into_inner().map_err(|err| {
+        log::error!("{:?}", err);
+        OrderExportErr::Internal
+    })
+}
+
+pub fn lines_to_xlsx(lines: &[OrderExportLine]) -> Result<Vec<u8>, OrderExportErr> {
+    let to_export_err = |err: XlsxError| {
+        log::error!("{:?}", err);
+        OrderExportErr::Internal
+    };
+
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let worksheet = workbook.add_worksheet();
+
+    worksheet.set_name("Orders").map_err(to_export_err)?;
+
+    for (col, header) in HEADERS.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(to_export_err)?;
+    }
+
+    for (row, line) in lines.iter().enumerate() {
+        let row = row as u32 + 1;
+
+        for (col, cell) in line.cells().iter().enumerate() {
+            let col = col as u16;
+
+            // Keep ids, quantities and prices numeric so they can be summed in the sheet.
+            let result = match col {
+                0 | 10 | 11 | 12 => {
+                    worksheet.write_number(row, col, cell.parse::<f64>().unwrap_or_default())
+                }
+                _ => worksheet.write_string(row, col, cell.as_ref()),
+            };
+
+            result.map_err(to_export_err)?;
+        }
+    }
+
+    worksheet.autofit();
+
+    workbook.save_to_buffer().map_err(to_export_err)
+}
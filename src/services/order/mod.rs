@@ -1,56 +1,157 @@
+pub mod export;
+pub mod order_status;
+
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 
+use chrono::{DateTime, Days, FixedOffset, NaiveDate};
 use entity::order::{self, Entity as Order};
+use entity::order_status_history::{self, Entity as OrderStatusHistory};
 use entity::product::{self, Entity as Product};
 use entity::products_in_order::{self, Entity as ProductsInOrder};
 use entity::{field, field_product};
+use rust_decimal::Decimal;
+use sea_orm::sea_query::{extension::postgres::PgExpr, Expr};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult, JoinType, QueryFilter,
-    QuerySelect, RelationTrait, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr,
+    EntityTrait, FromQueryResult, JoinType, Order as SortOrder, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, Select, Set, TransactionTrait,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::{
-    services::product::{ProductWithQuantityModel, ProductWithQuantitySerializable},
+    services::{
+        product::{ProductService, ProductWithQuantityModel, ProductWithQuantitySerializable},
+        webhook::{webhook_event::WebhookEvent, WebhookService},
+    },
     utilities::seaorm_utils::{parse_query_to_model, Prefixer},
 };
 
-use super::product::{FieldWithValue, ProductSerializable, ProductWithQuantityWithFieldModel};
+use super::product::{FieldWithValue, ProductWithQuantityWithFieldModel};
+
+use order_status::OrderStatus;
 
 #[derive(Clone, Debug)]
 pub enum OrderInsertionErr {
     Internal,
+    EmptyOrder,
     ProductNotFound(Vec<u32>),
+    DuplicatedProducts(Vec<u32>),
+    InvalidQuantity(Vec<u32>),
+    OutOfStock(Vec<u32>),
+    VariantRequired(Vec<u32>),
 }
 
 #[derive(Clone, Debug)]
 pub enum OrderGetError {
     Internal,
+    NotFound,
+}
+
+#[derive(Clone, Debug)]
+pub enum OrderStatusUpdateErr {
+    Internal,
+    NotFound,
+    InvalidTransition(OrderStatus, OrderStatus),
 }
 
+#[derive(Clone)]
 pub struct OrderService {
     db: DatabaseConnection,
+    webhooks: WebhookService,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Line of a new order. Products with variants are ordered as one of them,
+/// errors about such a line refer to the variant id.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct ProductWithQuantity {
     id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variant_id: Option<u32>,
     quantity: u32,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, ToSchema)]
+pub enum OrderSort {
+    #[serde(rename = "created_at_desc")]
+    #[default]
+    CreatedAtDesc,
+    #[serde(rename = "created_at_asc")]
+    CreatedAtAsc,
+    #[serde(rename = "id_desc")]
+    IdDesc,
+    #[serde(rename = "id_asc")]
+    IdAsc,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OrderFilter {
+    pub status: Option<OrderStatus>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub phone: Option<String>,
+    pub name: Option<String>,
+    pub sort: OrderSort,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct OrdersPage {
+    orders: Vec<OrderSerializable>,
+    total: u64,
+    page: u64,
+    page_count: u64,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct OrderSerializable {
+    pub id: u32,
+    pub name: String,
+    pub surname: String,
+    pub phone: String,
+    pub email: Option<String>,
+    pub address: String,
+    pub status: OrderStatus,
+    pub created_at: DateTime<FixedOffset>,
+    pub total: Decimal,
+    pub products: Vec<ProductWithQuantitySerializable>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrderInsertion {
+    pub id: u32,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct OrderStatusUpdate {
     id: u32,
-    name: String,
-    surname: String,
-    phone: String,
-    address: String,
-    products: Vec<ProductWithQuantitySerializable>,
+    status: OrderStatus,
 }
 
 #[derive(Clone, Debug, Serialize)]
-pub struct OrderInsertion {
+struct OrderStatusChange {
     id: u32,
+    from: OrderStatus,
+    to: OrderStatus,
+    admin_id: u32,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct OrderStatusHistorySerializable {
+    from_status: Option<OrderStatus>,
+    to_status: OrderStatus,
+    admin_id: Option<u32>,
+    created_at: DateTime<FixedOffset>,
+}
+
+impl From<order_status_history::Model> for OrderStatusHistorySerializable {
+    fn from(value: order_status_history::Model) -> Self {
+        Self {
+            from_status: value.from_status.map(Into::into),
+            to_status: value.to_status.into(),
+            admin_id: value.admin_id.map(|id| id as u32),
+            created_at: value.created_at,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -59,16 +160,19 @@ struct OrderWithProductsModel {
     pub name: String,
     pub surname: String,
     pub phone: String,
+    pub email: Option<String>,
     pub address: String,
-    pub product: ProductWithQuantityWithFieldModel,
+    pub status: i32,
+    pub created_at: DateTime<FixedOffset>,
+    pub product: Option<ProductWithQuantityWithFieldModel>,
 }
 
 impl FromQueryResult for OrderWithProductsModel {
     fn from_query_result(res: &sea_orm::prelude::QueryResult, _pre: &str) -> Result<Self, DbErr> {
         let order = parse_query_to_model::<order::Model, Order>(res)?;
         let products_in_order =
-            parse_query_to_model::<products_in_order::Model, ProductsInOrder>(res)?;
-        let product = parse_query_to_model::<product::Model, Product>(res)?;
+            parse_query_to_model::<products_in_order::Model, ProductsInOrder>(res).ok();
+        let product = parse_query_to_model::<product::Model, Product>(res).ok();
         let field_product =
             parse_query_to_model::<field_product::Model, field_product::Entity>(res).ok();
         let field = parse_query_to_model::<field::Model, field::Entity>(res).ok();
@@ -88,73 +192,134 @@ impl FromQueryResult for OrderWithProductsModel {
             None
         };
 
+        // A line is the snapshot stored with it, so later product edits don't
+        // rewrite order history. The product only adds what isn't part of it.
+        let product = products_in_order.map(|line| {
+            let (description, photo, stock, parent_id) = product
+                .map(|product| {
+                    (
+                        product.description,
+                        product.photo,
+                        product.stock,
+                        product.parent_id,
+                    )
+                })
+                .unwrap_or_default();
+
+            ProductWithQuantityWithFieldModel {
+                line_id: line.id,
+                id: line.product_id,
+                name: line.name,
+                price: line.price,
+                article: line.article,
+                description,
+                photo,
+                stock,
+                parent_id,
+                quantity: line.quantity,
+                field: field_with_value,
+            }
+        });
+
         Ok(OrderWithProductsModel {
             id: order.id,
             name: order.name,
             surname: order.surname,
             phone: order.phone,
+            email: order.email,
             address: order.address,
-            product: ProductWithQuantityWithFieldModel {
-                id: product.id,
-                name: product.name,
-                price: product.price,
-                article: product.article,
-                description: product.description,
-                photo: product.photo,
-                quantity: products_in_order.quantity,
-                field: field_with_value,
-            },
+            status: order.status,
+            created_at: order.created_at,
+            product,
         })
     }
 }
 
 impl OrderService {
+    const MAX_ORDERS_PER_PAGE: u64 = 100;
+    pub const MAX_PRODUCT_QUANTITY: u32 = 1000;
+
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            webhooks: WebhookService::new(db.clone()),
+            db,
+        }
     }
 
-    fn products_with_field_to_serializable(
-        products: Vec<ProductWithQuantityWithFieldModel>,
-    ) -> Vec<ProductWithQuantitySerializable> {
-        let mut product_index_map: HashMap<u32, usize> = HashMap::new();
-        let mut result: Vec<ProductWithQuantitySerializable> = Vec::new();
+    fn orders_with_products_to_serializable(
+        orders: Vec<OrderWithProductsModel>,
+    ) -> Vec<OrderSerializable> {
+        let mut order_index_map: HashMap<i32, usize> = HashMap::new();
+        let mut product_index_map: HashMap<i32, usize> = HashMap::new();
+        let mut result: Vec<OrderSerializable> = Vec::new();
+
+        for order in orders {
+            let order_index = match order_index_map.entry(order.id) {
+                Entry::Occupied(e) => *e.get(),
+                Entry::Vacant(e) => {
+                    result.push(OrderSerializable {
+                        id: order.id as u32,
+                        name: order.name,
+                        surname: order.surname,
+                        phone: order.phone,
+                        email: order.email,
+                        address: order.address,
+                        status: order.status.into(),
+                        created_at: order.created_at,
+                        total: Decimal::ZERO,
+                        products: Vec::new(),
+                    });
+
+                    *e.insert(result.len() - 1)
+                }
+            };
 
-        for (index, product) in products.iter().enumerate() {
-            let product_id = product.id as u32;
+            let Some(product) = order.product else {
+                continue;
+            };
 
-            if let Entry::Vacant(e) = product_index_map.entry(product_id) {
-                result.push(ProductWithQuantitySerializable {
-                    product: ProductSerializable {
+            let products = &mut result[order_index].products;
+
+            match product_index_map.entry(product.line_id) {
+                Entry::Occupied(e) => {
+                    if let Some(field) = product.field {
+                        products[*e.get()].product.fields.push(field.into());
+                    }
+                }
+                Entry::Vacant(e) => {
+                    let mut line: ProductWithQuantitySerializable = ProductWithQuantityModel {
                         id: product.id,
-                        name: product.name.to_owned(),
-                        price: product.price.to_owned(),
-                        article: product.article.to_owned(),
-                        description: product.description.to_owned(),
+                        name: product.name,
+                        price: product.price,
+                        article: product.article,
+                        description: product.description,
                         photo: product.photo,
+                        stock: product.stock,
+                        parent_id: product.parent_id,
+                        quantity: product.quantity,
                         fields: Vec::new(),
-                    },
-                    quantity: product.quantity as u32,
-                });
+                    }
+                    .into();
 
-                e.insert(index);
-
-                if let Some(field) = &product.field {
-                    result[index].product.fields.push(field.clone().into());
-                }
-            } else {
-                let key = product_index_map.get(&(product.id as u32)).unwrap();
+                    if let Some(field) = product.field {
+                        line.product.fields.push(field.into());
+                    }
 
-                if let Some(field) = &product.field {
-                    result[*key].product.fields.push(field.clone().into());
+                    products.push(line);
+                    e.insert(products.len() - 1);
                 }
             }
         }
 
-        return result;
+        for order in result.iter_mut() {
+            order.total = order.products.iter().map(|line| line.total).sum();
+        }
+
+        result
     }
 
-    pub async fn get_all(&self) -> Result<Vec<OrderSerializable>, OrderGetError> {
-        let select = Order::find()
+    fn orders_with_products_selector() -> Select<Order> {
+        let selector = Order::find()
             .join(JoinType::LeftJoin, order::Relation::ProductsInOrder.def())
             .join(
                 JoinType::LeftJoin,
@@ -163,53 +328,162 @@ impl OrderService {
             .join(JoinType::LeftJoin, product::Relation::FieldProduct.def())
             .join(JoinType::LeftJoin, field_product::Relation::Field.def());
 
-        let result = Prefixer::new(select)
+        Prefixer::new(selector)
             .add_columns(Order)
             .add_columns(ProductsInOrder)
             .add_columns(Product)
             .add_columns(field::Entity)
             .add_columns(field_product::Entity)
             .selector
+    }
+
+    fn filter_condition(filter: &OrderFilter) -> Condition {
+        let mut condition = Condition::all();
+
+        if let Some(status) = filter.status {
+            condition = condition.add(order::Column::Status.eq(i32::from(status)));
+        }
+
+        if let Some(from) = filter.from {
+            condition = condition.add(order::Column::CreatedAt.gte(from.and_hms_opt(0, 0, 0)));
+        }
+
+        if let Some(to) = filter.to.and_then(|to| to.checked_add_days(Days::new(1))) {
+            condition = condition.add(order::Column::CreatedAt.lt(to.and_hms_opt(0, 0, 0)));
+        }
+
+        if let Some(phone) = &filter.phone {
+            condition = condition.add(order::Column::Phone.contains(phone));
+        }
+
+        if let Some(name) = &filter.name {
+            let pattern = format!("%{name}%");
+
+            condition = condition.add(
+                Condition::any()
+                    .add(Expr::col((Order, order::Column::Name)).ilike(&pattern))
+                    .add(Expr::col((Order, order::Column::Surname)).ilike(&pattern)),
+            );
+        }
+
+        condition
+    }
+
+    fn sort_order(sort: OrderSort) -> (order::Column, SortOrder) {
+        match sort {
+            OrderSort::CreatedAtDesc => (order::Column::CreatedAt, SortOrder::Desc),
+            OrderSort::CreatedAtAsc => (order::Column::CreatedAt, SortOrder::Asc),
+            OrderSort::IdDesc => (order::Column::Id, SortOrder::Desc),
+            OrderSort::IdAsc => (order::Column::Id, SortOrder::Asc),
+        }
+    }
+
+    /// Ids of the orders matching the filter, in the requested order.
+    fn sorted_ids_selector(filter: &OrderFilter) -> Select<Order> {
+        let (sort_column, sort_order) = Self::sort_order(filter.sort);
+
+        Order::find()
+            .filter(Self::filter_condition(filter))
+            .select_only()
+            .column(order::Column::Id)
+            .order_by(sort_column, sort_order.clone())
+            .order_by(order::Column::Id, sort_order)
+    }
+
+    /// Loads orders with their lines, keeping the order of `ids`.
+    async fn orders_by_ids(&self, ids: &[i32]) -> Result<Vec<OrderSerializable>, OrderGetError> {
+        let result = Self::orders_with_products_selector()
+            .filter(order::Column::Id.is_in(ids.to_vec()))
             .into_model::<OrderWithProductsModel>()
             .all(&self.db)
             .await
             .map_err(|_| OrderGetError::Internal)?;
 
-        let mut order_index_map: HashMap<i32, usize> = HashMap::new();
-        let mut response: Vec<OrderSerializable> = Vec::new();
-        let products: Vec<ProductWithQuantityWithFieldModel> =
-            result.iter().map(|order| order.product.clone()).collect();
-        let serializable_products: HashMap<i32, ProductWithQuantitySerializable> =
-            Self::products_with_field_to_serializable(products)
-                .into_iter()
-                .map(|product| (product.product.id, product))
-                .collect();
-
-        for (index, order) in result.iter().enumerate() {
-            if let Entry::Vacant(e) = order_index_map.entry(order.id) {
-                response.push(OrderSerializable {
-                    id: order.id as u32,
-                    name: order.name.clone(),
-                    surname: order.surname.clone(),
-                    phone: order.phone.clone(),
-                    address: order.address.clone(),
-                    products: Vec::new(),
-                });
-
-                e.insert(index);
-
-                response[index]
-                    .products
-                    .push(serializable_products[&order.product.id].clone());
-            } else {
-                let key = order_index_map.get(&order.id).unwrap();
-                response[*key]
-                    .products
-                    .push(serializable_products[&order.product.id].clone());
+        let positions: HashMap<u32, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (*id as u32, position))
+            .collect();
+        let mut orders = Self::orders_with_products_to_serializable(result);
+
+        orders.sort_by_key(|order| positions[&order.id]);
+        self.attach_photos(&mut orders).await?;
+
+        Ok(orders)
+    }
+
+    pub async fn get_all(
+        &self,
+        filter: &OrderFilter,
+        page: u64,
+        page_size: u64,
+    ) -> Result<OrdersPage, OrderGetError> {
+        let page_size = page_size.clamp(1, OrderService::MAX_ORDERS_PER_PAGE);
+
+        let total = Order::find()
+            .filter(Self::filter_condition(filter))
+            .count(&self.db)
+            .await
+            .map_err(|_| OrderGetError::Internal)?;
+
+        // Pick the page of order ids first, otherwise every product line and
+        // field of the joined query below would take a slot on the page.
+        let ids: Vec<i32> = Self::sorted_ids_selector(filter)
+            .offset(page * page_size)
+            .limit(page_size)
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|_| OrderGetError::Internal)?;
+
+        let orders = self.orders_by_ids(&ids).await?;
+
+        Ok(OrdersPage {
+            orders,
+            total,
+            page: page + 1,
+            page_count: total.div_ceil(page_size),
+        })
+    }
+
+    pub async fn get(&self, id: u32) -> Result<OrderSerializable, OrderGetError> {
+        let result = Self::orders_with_products_selector()
+            .filter(order::Column::Id.eq(id as i32))
+            .into_model::<OrderWithProductsModel>()
+            .all(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                OrderGetError::Internal
+            })?;
+
+        let mut orders = Self::orders_with_products_to_serializable(result);
+
+        self.attach_photos(&mut orders).await?;
+
+        orders.into_iter().next().ok_or(OrderGetError::NotFound)
+    }
+
+    async fn attach_photos(&self, orders: &mut [OrderSerializable]) -> Result<(), OrderGetError> {
+        let ids: Vec<i32> = orders
+            .iter()
+            .flat_map(|order| order.products.iter().filter_map(|line| line.product.id))
+            .collect();
+        let photos = ProductService::photos_by_product(&self.db, &ids)
+            .await
+            .map_err(|_| OrderGetError::Internal)?;
+
+        for order in orders.iter_mut() {
+            for line in order.products.iter_mut() {
+                line.product.photos = line
+                    .product
+                    .id
+                    .and_then(|id| photos.get(&id).cloned())
+                    .unwrap_or_default();
             }
         }
 
-        Ok(response)
+        Ok(())
     }
 
     pub async fn create(
@@ -217,6 +491,7 @@ impl OrderService {
         name: String,
         surname: String,
         phone: String,
+        email: Option<String>,
         address: String,
         products: Vec<ProductWithQuantity>,
     ) -> Result<OrderInsertion, OrderInsertionErr> {
@@ -224,62 +499,754 @@ impl OrderService {
             name: Set(name),
             surname: Set(surname),
             phone: Set(phone),
+            email: Set(email),
             address: Set(address),
             ..Default::default()
         };
 
-        let set_of_products: HashMap<u32, u32> = products
-            .clone()
+        if products.is_empty() {
+            return Err(OrderInsertionErr::EmptyOrder);
+        }
+
+        // From here on a line with a variant is the line of the variant,
+        // the product id only has to be its parent.
+        let requested_parents: HashMap<u32, u32> = products
+            .iter()
+            .filter_map(|product| Some((product.variant_id?, product.id)))
+            .collect();
+        let products: Vec<ProductWithQuantity> = products
             .into_iter()
-            .map(|pr| (pr.id, pr.quantity))
+            .map(|product| ProductWithQuantity {
+                id: product.variant_id.unwrap_or(product.id),
+                variant_id: None,
+                quantity: product.quantity,
+            })
+            .collect();
+
+        let mut ids: HashSet<u32> = HashSet::new();
+        let mut duplicated_products: Vec<u32> = Vec::new();
+
+        for product in products.iter() {
+            if !ids.insert(product.id) && !duplicated_products.contains(&product.id) {
+                duplicated_products.push(product.id);
+            }
+        }
+
+        if !duplicated_products.is_empty() {
+            return Err(OrderInsertionErr::DuplicatedProducts(duplicated_products));
+        }
+
+        let invalid_quantities: Vec<u32> = products
+            .iter()
+            .filter(|product| {
+                product.quantity == 0 || product.quantity > OrderService::MAX_PRODUCT_QUANTITY
+            })
+            .map(|product| product.id)
             .collect();
 
-        let ids: HashSet<u32> = set_of_products.keys().copied().collect();
+        if !invalid_quantities.is_empty() {
+            return Err(OrderInsertionErr::InvalidQuantity(invalid_quantities));
+        }
 
-        let not_found_products: Vec<u32> = Product::find()
-            .filter(product::Column::Id.is_in(ids.clone()))
+        // Ids are bound as the column's int4 everywhere, a statement cached with
+        // int8 parameters would otherwise be reused for the same query text.
+        let db_ids: Vec<i32> = ids.iter().map(|id| *id as i32).collect();
+        let found_products: HashMap<u32, product::Model> = Product::find()
+            .filter(product::Column::Id.is_in(db_ids.clone()))
             .all(&self.db)
             .await
             .map(|prdcts| {
-                let result = prdcts
-                    .iter()
-                    .map(|product| product.id as u32)
-                    .collect::<HashSet<u32>>();
-
-                ids.difference(&result).copied().collect()
+                prdcts
+                    .into_iter()
+                    .map(|product| (product.id as u32, product))
+                    .collect()
             })
             .map_err(|_| OrderInsertionErr::Internal)?;
 
+        let not_found_products: Vec<u32> = ids
+            .iter()
+            .filter(|id| {
+                let Some(product) = found_products.get(id) else {
+                    return true;
+                };
+
+                requested_parents
+                    .get(id)
+                    .is_some_and(|parent| product.parent_id != Some(*parent as i32))
+            })
+            .copied()
+            .collect();
+
         if !not_found_products.is_empty() {
             return Err(OrderInsertionErr::ProductNotFound(not_found_products));
         }
 
-        let insertion_result = self
+        let mut variant_required: Vec<u32> = Product::find()
+            .select_only()
+            .column(product::Column::ParentId)
+            .distinct()
+            .filter(product::Column::ParentId.is_in(db_ids))
+            .into_tuple::<i32>()
+            .all(&self.db)
+            .await
+            .map_err(|_| OrderInsertionErr::Internal)?
+            .into_iter()
+            .map(|id| id as u32)
+            .collect();
+
+        if !variant_required.is_empty() {
+            variant_required.sort();
+
+            return Err(OrderInsertionErr::VariantRequired(variant_required));
+        }
+
+        let parent_names: HashMap<i32, String> = Product::find()
+            .filter(
+                product::Column::Id.is_in(
+                    found_products
+                        .values()
+                        .filter_map(|product| product.parent_id),
+                ),
+            )
+            .all(&self.db)
+            .await
+            .map_err(|_| OrderInsertionErr::Internal)?
+            .into_iter()
+            .map(|parent| (parent.id, parent.name))
+            .collect();
+
+        let transaction = self
             .db
-            .transaction::<_, OrderInsertion, DbErr>(move |tx| {
-                Box::pin(async move {
-                    let insertion_result =
-                        Order::insert(model)
-                            .exec(tx)
-                            .await
-                            .map(|result| OrderInsertion {
-                                id: result.last_insert_id as u32,
-                            })?;
-
-                    ProductsInOrder::insert_many(products.iter().map(|product| {
-                        products_in_order::ActiveModel {
-                            product_id: Set(product.id as i32),
-                            order_id: Set(insertion_result.id as i32),
-                            quantity: Set(product.quantity as i32),
-                        }
-                    }));
-
-                    Ok(insertion_result)
-                })
+            .begin()
+            .await
+            .map_err(|_| OrderInsertionErr::Internal)?;
+
+        let mut out_of_stock: Vec<u32> = Vec::new();
+        let mut reservations: Vec<&ProductWithQuantity> = products.iter().collect();
+
+        // Rows are always locked in the same order so that concurrent orders
+        // can't deadlock. Untracked products (NULL stock) are left as is,
+        // tracked ones are reserved only when there is enough left.
+        reservations.sort_by_key(|product| product.id);
+
+        for product in reservations {
+            let quantity = product.quantity as i32;
+            let result = Product::update_many()
+                .col_expr(
+                    product::Column::Stock,
+                    Expr::col(product::Column::Stock).sub(quantity),
+                )
+                .filter(product::Column::Id.eq(product.id as i32))
+                .filter(
+                    Condition::any()
+                        .add(product::Column::Stock.is_null())
+                        .add(product::Column::Stock.gte(quantity)),
+                )
+                .exec(&transaction)
+                .await
+                .map_err(|_| OrderInsertionErr::Internal)?;
+
+            if result.rows_affected == 0 {
+                out_of_stock.push(product.id);
+            }
+        }
+
+        if !out_of_stock.is_empty() {
+            return Err(OrderInsertionErr::OutOfStock(out_of_stock));
+        }
+
+        let insertion_result = Order::insert(model)
+            .exec(&transaction)
+            .await
+            .map(|result| OrderInsertion {
+                id: result.last_insert_id as u32,
             })
+            .map_err(|_| OrderInsertionErr::Internal)?;
+
+        OrderStatusHistory::insert(order_status_history::ActiveModel {
+            order_id: Set(insertion_result.id as i32),
+            from_status: Set(None),
+            to_status: Set(OrderStatus::New.into()),
+            admin_id: Set(None),
+            ..Default::default()
+        })
+        .exec(&transaction)
+        .await
+        .map_err(|_| OrderInsertionErr::Internal)?;
+
+        ProductsInOrder::insert_many(products.iter().map(|product| {
+            let snapshot = &found_products[&product.id];
+            let name = match snapshot.parent_id.and_then(|id| parent_names.get(&id)) {
+                Some(parent_name) => format!("{}, {}", parent_name, snapshot.name),
+                None => snapshot.name.to_owned(),
+            };
+
+            products_in_order::ActiveModel {
+                product_id: Set(Some(product.id as i32)),
+                order_id: Set(insertion_result.id as i32),
+                quantity: Set(product.quantity as i32),
+                name: Set(name),
+                article: Set(snapshot.article.to_owned()),
+                price: Set(snapshot.price),
+                ..Default::default()
+            }
+        }))
+        .exec(&transaction)
+        .await
+        .map_err(|err| {
+            log::error!("{:?}", err);
+            OrderInsertionErr::Internal
+        })?;
+
+        transaction
+            .commit()
             .await
             .map_err(|_| OrderInsertionErr::Internal)?;
 
+        let service = self.clone();
+        let id = insertion_result.id;
+
+        actix_web::rt::spawn(async move {
+            match service.get(id).await {
+                Ok(order) => service
+                    .webhooks
+                    .dispatch(WebhookEvent::OrderCreated, &order),
+                Err(err) => log::error!("Order {} webhook skipped: {:?}", id, err),
+            }
+        });
+
         Ok(insertion_result)
     }
+
+    async fn restore_stock(transaction: &DatabaseTransaction, order_id: u32) -> Result<(), DbErr> {
+        let lines = ProductsInOrder::find()
+            .filter(products_in_order::Column::OrderId.eq(order_id as i32))
+            .order_by_asc(products_in_order::Column::ProductId)
+            .all(transaction)
+            .await?;
+
+        // Stock of deleted products is gone with them.
+        for line in lines {
+            let Some(product_id) = line.product_id else {
+                continue;
+            };
+
+            Product::update_many()
+                .col_expr(
+                    product::Column::Stock,
+                    Expr::col(product::Column::Stock).add(line.quantity),
+                )
+                .filter(product::Column::Id.eq(product_id))
+                .filter(product::Column::Stock.is_not_null())
+                .exec(transaction)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_status(
+        &self,
+        id: u32,
+        status: OrderStatus,
+        admin_id: i32,
+    ) -> Result<OrderStatusUpdate, OrderStatusUpdateErr> {
+        let transaction = self
+            .db
+            .begin()
+            .await
+            .map_err(|_| OrderStatusUpdateErr::Internal)?;
+
+        let order = Order::find_by_id(id as i32)
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .map_err(|_| OrderStatusUpdateErr::Internal)?
+            .ok_or(OrderStatusUpdateErr::NotFound)?;
+
+        let current: OrderStatus = order.status.into();
+
+        if !current.can_transition_to(status) {
+            return Err(OrderStatusUpdateErr::InvalidTransition(current, status));
+        }
+
+        let mut order: order::ActiveModel = order.into();
+
+        order.status = Set(status.into());
+        order
+            .update(&transaction)
+            .await
+            .map_err(|_| OrderStatusUpdateErr::Internal)?;
+
+        if status == OrderStatus::Cancelled {
+            Self::restore_stock(&transaction, id)
+                .await
+                .map_err(|_| OrderStatusUpdateErr::Internal)?;
+        }
+
+        OrderStatusHistory::insert(order_status_history::ActiveModel {
+            order_id: Set(id as i32),
+            from_status: Set(Some(current.into())),
+            to_status: Set(status.into()),
+            admin_id: Set(Some(admin_id)),
+            ..Default::default()
+        })
+        .exec(&transaction)
+        .await
+        .map_err(|_| OrderStatusUpdateErr::Internal)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|_| OrderStatusUpdateErr::Internal)?;
+
+        self.webhooks.dispatch(
+            WebhookEvent::OrderStatusChanged,
+            &OrderStatusChange {
+                id,
+                from: current,
+                to: status,
+                admin_id: admin_id as u32,
+            },
+        );
+
+        Ok(OrderStatusUpdate { id, status })
+    }
+
+    pub async fn status_history(
+        &self,
+        id: u32,
+    ) -> Result<Vec<OrderStatusHistorySerializable>, OrderGetError> {
+        let order = Order::find_by_id(id as i32)
+            .one(&self.db)
+            .await
+            .map_err(|_| OrderGetError::Internal)?;
+
+        if order.is_none() {
+            return Err(OrderGetError::NotFound);
+        }
+
+        OrderStatusHistory::find()
+            .filter(order_status_history::Column::OrderId.eq(id as i32))
+            .order_by_asc(order_status_history::Column::CreatedAt)
+            .order_by_asc(order_status_history::Column::Id)
+            .all(&self.db)
+            .await
+            .map(|history| history.into_iter().map(Into::into).collect())
+            .map_err(|_| OrderGetError::Internal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use sea_orm::{EntityTrait, PaginatorTrait, Set};
+
+    use entity::admin::{self, Entity as Admin};
+    use entity::order::Entity as Order;
+    use entity::product::{self, Entity as Product};
+
+    use super::{OrderFilter, OrderInsertionErr, OrderService, OrderStatus, ProductWithQuantity};
+    use crate::services::product::ProductService;
+    use crate::utilities::test_utils::TestDb;
+
+    async fn insert_product(
+        service: &OrderService,
+        name: &str,
+        price: rust_decimal::Decimal,
+    ) -> u32 {
+        Product::insert(product::ActiveModel {
+            name: Set(name.to_owned()),
+            price: Set(price),
+            article: Set(format!("{name}-article")),
+            description: Set(format!("{name} description")),
+            ..Default::default()
+        })
+        .exec(&service.db)
+        .await
+        .unwrap()
+        .last_insert_id as u32
+    }
+
+    async fn create_order(
+        service: &OrderService,
+        products: Vec<ProductWithQuantity>,
+    ) -> Result<super::OrderInsertion, OrderInsertionErr> {
+        service
+            .create(
+                "Ivan".to_owned(),
+                "Petrov".to_owned(),
+                "+79120000000".to_owned(),
+                None,
+                "Moscow".to_owned(),
+                products,
+            )
+            .await
+    }
+
+    #[actix_web::test]
+    async fn created_order_reads_back_with_its_lines() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100.50)).await;
+        let chair = insert_product(&service, "Chair", dec!(20)).await;
+
+        let insertion = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 2,
+                },
+                ProductWithQuantity {
+                    id: chair,
+                    variant_id: None,
+                    quantity: 4,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        let page = service
+            .get_all(&OrderFilter::default(), 0, 15)
+            .await
+            .unwrap();
+
+        assert_eq!(page.total, 1);
+        assert_eq!(page.orders.len(), 1);
+
+        let order = &page.orders[0];
+        let mut lines: Vec<(Option<i32>, u32)> = order
+            .products
+            .iter()
+            .map(|line| (line.product.id, line.quantity))
+            .collect();
+
+        lines.sort();
+
+        assert_eq!(order.id, insertion.id);
+        assert_eq!(
+            lines,
+            vec![(Some(table as i32), 2), (Some(chair as i32), 4)]
+        );
+        assert_eq!(order.total, dec!(281.00));
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn rejects_zero_and_oversized_quantities() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100)).await;
+        let chair = insert_product(&service, "Chair", dec!(20)).await;
+
+        let result = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 0,
+                },
+                ProductWithQuantity {
+                    id: chair,
+                    variant_id: None,
+                    quantity: OrderService::MAX_PRODUCT_QUANTITY + 1,
+                },
+            ],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::InvalidQuantity(ids)) if ids == vec![table, chair]
+        ));
+        assert_eq!(Order::find().count(&test_db.db).await.unwrap(), 0);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn rejects_duplicated_products() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100)).await;
+
+        let result = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 1,
+                },
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 3,
+                },
+            ],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::DuplicatedProducts(ids)) if ids == vec![table]
+        ));
+        assert_eq!(Order::find().count(&test_db.db).await.unwrap(), 0);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn rejects_unknown_products_without_creating_order() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100)).await;
+
+        let result = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 1,
+                },
+                ProductWithQuantity {
+                    id: 9999,
+                    variant_id: None,
+                    quantity: 1,
+                },
+            ],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::ProductNotFound(ids)) if ids == vec![9999]
+        ));
+        assert_eq!(Order::find().count(&test_db.db).await.unwrap(), 0);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn reserves_stock_and_restores_it_on_cancel() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100)).await;
+        let chair = insert_product(&service, "Chair", dec!(20)).await;
+        let stock = |id: u32| {
+            let db = test_db.db.clone();
+
+            async move {
+                Product::find_by_id(id as i32)
+                    .one(&db)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .stock
+            }
+        };
+
+        Product::update(product::ActiveModel {
+            id: Set(table as i32),
+            stock: Set(Some(3)),
+            ..Default::default()
+        })
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+
+        let result = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 4,
+                },
+                ProductWithQuantity {
+                    id: chair,
+                    variant_id: None,
+                    quantity: 1,
+                },
+            ],
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::OutOfStock(ids)) if ids == vec![table]
+        ));
+        assert_eq!(stock(table).await, Some(3));
+
+        let insertion = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: table,
+                    variant_id: None,
+                    quantity: 2,
+                },
+                ProductWithQuantity {
+                    id: chair,
+                    variant_id: None,
+                    quantity: 1,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(stock(table).await, Some(1));
+        assert_eq!(stock(chair).await, None);
+
+        let admin = Admin::insert(admin::ActiveModel {
+            username: Set("admin".to_owned()),
+            password: Set("password".to_owned()),
+            ..Default::default()
+        })
+        .exec(&test_db.db)
+        .await
+        .unwrap()
+        .last_insert_id;
+
+        service
+            .update_status(insertion.id, OrderStatus::Cancelled, admin)
+            .await
+            .unwrap();
+
+        assert_eq!(stock(table).await, Some(3));
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn orders_products_with_variants_as_a_chosen_variant() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let table = insert_product(&service, "Table", dec!(100)).await;
+        let chair = insert_product(&service, "Chair", dec!(20)).await;
+        let wide = insert_product(&service, "160 cm", dec!(150)).await;
+
+        Product::update(product::ActiveModel {
+            id: Set(wide as i32),
+            stock: Set(Some(2)),
+            parent_id: Set(Some(table as i32)),
+            ..Default::default()
+        })
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+
+        let line = |id: u32, variant_id: Option<u32>| ProductWithQuantity {
+            id,
+            variant_id,
+            quantity: 1,
+        };
+
+        let result = create_order(&service, vec![line(table, None)]).await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::VariantRequired(ids)) if ids == vec![table]
+        ));
+
+        let result = create_order(&service, vec![line(chair, Some(wide))]).await;
+
+        assert!(matches!(
+            result,
+            Err(OrderInsertionErr::ProductNotFound(ids)) if ids == vec![wide]
+        ));
+
+        let insertion = create_order(&service, vec![line(table, Some(wide))])
+            .await
+            .unwrap();
+        let order = service.get(insertion.id).await.unwrap();
+        let ordered = &order.products[0];
+
+        assert_eq!(ordered.product.id, Some(wide as i32));
+        assert_eq!(ordered.product.name, "Table, 160 cm");
+        assert_eq!(ordered.product.price, dec!(150));
+        assert_eq!(
+            Product::find_by_id(wide as i32)
+                .one(&test_db.db)
+                .await
+                .unwrap()
+                .unwrap()
+                .stock,
+            Some(1)
+        );
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn keeps_lines_of_deleted_products() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = OrderService::new(test_db.db.clone());
+        let products = ProductService::new(test_db.db.clone());
+        let lamp = insert_product(&service, "Lamp", dec!(30)).await;
+        let chair = insert_product(&service, "Chair", dec!(20)).await;
+        let insertion = create_order(
+            &service,
+            vec![
+                ProductWithQuantity {
+                    id: lamp,
+                    variant_id: None,
+                    quantity: 1,
+                },
+                ProductWithQuantity {
+                    id: chair,
+                    variant_id: None,
+                    quantity: 2,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        Product::update(product::ActiveModel {
+            id: Set(lamp as i32),
+            name: Set("Renamed lamp".to_owned()),
+            price: Set(dec!(45)),
+            ..Default::default()
+        })
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+
+        products.delete(&[lamp, chair]).await.unwrap();
+
+        let order = service.get(insertion.id).await.unwrap();
+        let mut lines: Vec<_> = order
+            .products
+            .iter()
+            .map(|line| (line.product.id, line.product.name.as_str(), line.quantity))
+            .collect();
+        lines.sort_by_key(|line| line.1);
+
+        assert_eq!(lines, [(None, "Chair", 2), (None, "Lamp", 1)]);
+        assert_eq!(order.total, dec!(70));
+
+        test_db.drop().await;
+    }
 }
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash, ToSchema)]
+pub enum OrderStatus {
+    #[serde(rename = "new")]
+    #[default]
+    New,
+    #[serde(rename = "confirmed")]
+    Confirmed,
+    #[serde(rename = "shipped")]
+    Shipped,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+impl OrderStatus {
+    /// Statuses an order is allowed to move to from the current one.
+    /// `Completed` and `Cancelled` are terminal.
+    pub fn next_statuses(&self) -> &'static [OrderStatus] {
+        match self {
+            OrderStatus::New => &[OrderStatus::Confirmed, OrderStatus::Cancelled],
+            OrderStatus::Confirmed => &[OrderStatus::Shipped, OrderStatus::Cancelled],
+            OrderStatus::Shipped => &[OrderStatus::Completed, OrderStatus::Cancelled],
+            OrderStatus::Completed | OrderStatus::Cancelled => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        self.next_statuses().contains(&next)
+    }
+
+    /// Same value as the serialized one.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::New => "new",
+            OrderStatus::Confirmed => "confirmed",
+            OrderStatus::Shipped => "shipped",
+            OrderStatus::Completed => "completed",
+            OrderStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl From<i32> for OrderStatus {
+    fn from(value: i32) -> Self {
+        match value {
+            2 => OrderStatus::Confirmed,
+            3 => OrderStatus::Shipped,
+            4 => OrderStatus::Completed,
+            5 => OrderStatus::Cancelled,
+            _ => OrderStatus::New,
+        }
+    }
+}
+
+impl From<OrderStatus> for i32 {
+    fn from(value: OrderStatus) -> Self {
+        match value {
+            OrderStatus::New => 1,
+            OrderStatus::Confirmed => 2,
+            OrderStatus::Shipped => 3,
+            OrderStatus::Completed => 4,
+            OrderStatus::Cancelled => 5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrderStatus::{self, *};
+
+    const ALL: [OrderStatus; 5] = [New, Confirmed, Shipped, Completed, Cancelled];
+
+    #[test]
+    fn transitions_follow_the_table() {
+        let allowed = [
+            (New, Confirmed),
+            (New, Cancelled),
+            (Confirmed, Shipped),
+            (Confirmed, Cancelled),
+            (Shipped, Completed),
+            (Shipped, Cancelled),
+        ];
+
+        for from in ALL {
+            for to in ALL {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&(from, to)),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn maps_to_and_from_i32() {
+        let table = [
+            (New, 1),
+            (Confirmed, 2),
+            (Shipped, 3),
+            (Completed, 4),
+            (Cancelled, 5),
+        ];
+
+        for (status, value) in table {
+            assert_eq!(i32::from(status), value);
+            assert_eq!(OrderStatus::from(value), status);
+        }
+        assert_eq!(OrderStatus::from(0), New);
+        assert_eq!(OrderStatus::from(6), New);
+    }
+}
@@ -0,0 +1,111 @@
+use phonenumber::{country, Mode};
+
+#[derive(Copy, Clone, Debug)]
+pub enum PhoneErr {
+    Invalid,
+}
+
+pub trait PhoneConfigProvider {
+    /// ISO 3166-1 alpha-2 code used for numbers written without a country code.
+    fn phone_region(&self) -> &str;
+}
+
+pub struct PhoneService {
+    region: country::Id,
+}
+
+impl PhoneService {
+    pub fn new(region: country::Id) -> Self {
+        Self { region }
+    }
+
+    pub fn from_config<T>(config: &T) -> Self
+    where
+        T: PhoneConfigProvider,
+    {
+        let region = config
+            .phone_region()
+            .to_uppercase()
+            .parse()
+            .unwrap_or_else(|_| {
+                log::warn!(
+                    "Unknown PHONE_REGION {}. Falling back to RU",
+                    config.phone_region()
+                );
+
+                country::Id::RU
+            });
+
+        Self::new(region)
+    }
+
+    /// Parses the number as typed by a customer and returns it in E.164,
+    /// e.g. `8 (912) 000-00-00` becomes `+79120000000`.
+    pub fn normalize(&self, raw: &str) -> Result<String, PhoneErr> {
+        let number = phonenumber::parse(Some(self.region), raw).map_err(|_| PhoneErr::Invalid)?;
+
+        if !number.is_valid() {
+            return Err(PhoneErr::Invalid);
+        }
+
+        Ok(number.format().mode(Mode::E164).to_string())
+    }
+
+    /// What to look for in stored E.164 numbers: the whole number when it
+    /// parses, otherwise just its digits, so `912 000` still finds a part.
+    pub fn search_term(&self, raw: &str) -> String {
+        self.normalize(raw).unwrap_or_else(|_| {
+            let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+
+            if digits.is_empty() {
+                raw.to_owned()
+            } else {
+                digits
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use phonenumber::country;
+
+    use super::PhoneService;
+
+    #[test]
+    fn normalizes_local_and_international_formats() {
+        let service = PhoneService::new(country::Id::RU);
+
+        for raw in [
+            "8 (912) 000-00-00",
+            "+7 912 000 00 00",
+            "79120000000",
+            "9120000000",
+        ] {
+            assert_eq!(service.normalize(raw).unwrap(), "+79120000000", "{raw}");
+        }
+
+        assert_eq!(
+            service.normalize("+44 20 7946 0958").unwrap(),
+            "+442079460958"
+        );
+    }
+
+    #[test]
+    fn searches_by_whole_number_or_its_digits() {
+        let service = PhoneService::new(country::Id::RU);
+
+        assert_eq!(service.search_term("8 (912) 000-00-00"), "+79120000000");
+        assert_eq!(service.search_term("+7 912 000 00 00"), "+79120000000");
+        assert_eq!(service.search_term("(912) 000"), "912000");
+    }
+
+    #[test]
+    fn rejects_garbage_and_incomplete_numbers() {
+        let service = PhoneService::new(country::Id::RU);
+
+        for raw in ["", "phone", "8 (912) 000", "+7 000 000 00 00"] {
+            assert!(service.normalize(raw).is_err(), "{raw}");
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet};
+
+use entity::{category_product, field, field_product, product};
+use migration::{Expr, Func, Query, SelectStatement};
+use sea_orm::{
+    ColumnTrait, Condition, EntityTrait, FromQueryResult, JoinType, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{search::matches_search, ProductService, ProductServiceErr};
+use crate::services::field::field_type::FieldType;
+
+/// Integer field values are stored as text, anything that isn't a number is left out of ranges.
+const VALUE_AS_BIGINT: &str = r#"CASE WHEN "field_product"."value" ~ '^-?[0-9]{1,18}$'
+    THEN "field_product"."value"::bigint END"#;
+
+/// Condition on the value of one field, as given by the client.
+/// Checked against the type of the field when the products are queried.
+#[derive(Clone, Debug)]
+pub enum FieldCondition {
+    Equals(String),
+    Range {
+        min: Option<String>,
+        max: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldFilter {
+    pub field_id: u32,
+    pub condition: FieldCondition,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct FacetValue {
+    value: String,
+    count: u64,
+}
+
+/// Values of a field among the matching products, with the number of products having each.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct FieldFacet {
+    id: u32,
+    name: String,
+    r#type: FieldType,
+    values: Vec<FacetValue>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct FacetRow {
+    id: i32,
+    name: String,
+    r#type: i32,
+    value: String,
+    count: i64,
+}
+
+impl ProductService {
+    /// Products matching every filter, directly or through one of their variants.
+    /// Strings compare as is, integer fields compare as numbers and also accept ranges.
+    pub(super) async fn field_filters_condition(
+        &self,
+        filters: &[FieldFilter],
+    ) -> Result<Condition, ProductServiceErr> {
+        let mut condition = Condition::all();
+
+        if filters.is_empty() {
+            return Ok(condition);
+        }
+
+        let ids: HashSet<i32> = filters
+            .iter()
+            .map(|filter| filter.field_id as i32)
+            .collect();
+        let types: HashMap<u32, FieldType> = field::Entity::find()
+            .filter(field::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .into_iter()
+            .map(|field| (field.id as u32, field.r#type.into()))
+            .collect();
+
+        for filter in filters {
+            let invalid = || ProductServiceErr::InvalidFieldFilter(filter.field_id);
+            let field_type = types.get(&filter.field_id).ok_or_else(invalid)?;
+
+            let value_condition = match (field_type, &filter.condition) {
+                (FieldType::Integer, FieldCondition::Equals(value)) => {
+                    let value = parse_integer(value).ok_or_else(invalid)?;
+
+                    value_as_bigint().eq(value)
+                }
+                (FieldType::Integer, FieldCondition::Range { min, max }) => {
+                    let min = min.as_deref().map(parse_integer);
+                    let max = max.as_deref().map(parse_integer);
+                    let mut range = value_as_bigint().is_not_null();
+
+                    if let Some(min) = min {
+                        range = range.and(value_as_bigint().gte(min.ok_or_else(invalid)?));
+                    }
+
+                    if let Some(max) = max {
+                        range = range.and(value_as_bigint().lte(max.ok_or_else(invalid)?));
+                    }
+
+                    range
+                }
+                (_, FieldCondition::Equals(value)) => {
+                    Expr::col((field_product::Entity, field_product::Column::Value)).eq(value)
+                }
+                (_, FieldCondition::Range { .. }) => return Err(invalid()),
+            };
+
+            let products = Query::select()
+                .column(field_product::Column::ProductId)
+                .from(field_product::Entity)
+                .and_where(field_product::Column::FieldId.eq(filter.field_id as i32))
+                .and_where(value_condition)
+                .to_owned();
+
+            condition = condition.add(self_or_variant_in(products));
+        }
+
+        Ok(condition)
+    }
+
+    /// Facets of the products in a category, or matching the search text,
+    /// or of the whole catalog when neither is given. Values of variants count
+    /// along with the values of their parents, a product and its variants
+    /// sharing a value count once.
+    pub async fn facets(
+        &self,
+        category_id: Option<u32>,
+        text: &str,
+    ) -> Result<Vec<FieldFacet>, ProductServiceErr> {
+        let mut selector = field_product::Entity::find()
+            .select_only()
+            .column(field::Column::Id)
+            .column(field::Column::Name)
+            .column(field::Column::Type)
+            .column(field_product::Column::Value)
+            .column_as(
+                Expr::expr(Func::count_distinct(Func::coalesce([
+                    Expr::col((product::Entity, product::Column::ParentId)).into(),
+                    Expr::col((product::Entity, product::Column::Id)).into(),
+                ]))),
+                "count",
+            )
+            .join(JoinType::InnerJoin, field_product::Relation::Field.def())
+            .join(JoinType::InnerJoin, field_product::Relation::Product.def())
+            .group_by(field::Column::Id)
+            .group_by(field::Column::Name)
+            .group_by(field::Column::Type)
+            .group_by(field_product::Column::Value)
+            .order_by_asc(field::Column::Id)
+            .order_by_asc(field_product::Column::Value);
+
+        if let Some(category_id) = category_id {
+            let products = Query::select()
+                .column(category_product::Column::ProductId)
+                .from(category_product::Entity)
+                .and_where(category_product::Column::CategoryId.eq(category_id as i32))
+                .to_owned();
+
+            selector = selector.filter(self_or_parent_in(products));
+        }
+
+        if !text.is_empty() {
+            let products = Query::select()
+                .column(product::Column::Id)
+                .from(product::Entity)
+                .and_where(matches_search(text))
+                .to_owned();
+
+            selector = selector.filter(self_or_parent_in(products));
+        }
+
+        let rows = selector
+            .into_model::<FacetRow>()
+            .all(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        let mut facets: Vec<FieldFacet> = Vec::new();
+
+        for row in rows {
+            let value = FacetValue {
+                value: row.value,
+                count: row.count as u64,
+            };
+
+            match facets.last_mut() {
+                Some(facet) if facet.id == row.id as u32 => facet.values.push(value),
+                _ => facets.push(FieldFacet {
+                    id: row.id as u32,
+                    name: row.name,
+                    r#type: row.r#type.into(),
+                    values: vec![value],
+                }),
+            }
+        }
+
+        // Text order would put 100 before 20.
+        for facet in facets.iter_mut() {
+            if facet.r#type == FieldType::Integer {
+                facet.values.sort_by_key(|value| {
+                    let number = parse_integer(&value.value);
+
+                    (number.is_none(), number)
+                });
+            }
+        }
+
+        Ok(facets)
+    }
+}
+
+/// Products among `products`, or having a variant among them.
+pub(super) fn self_or_variant_in(products: SelectStatement) -> Condition {
+    let parents = Query::select()
+        .column(product::Column::ParentId)
+        .from(product::Entity)
+        .and_where(product::Column::Id.in_subquery(products.clone()))
+        .to_owned();
+
+    Condition::any()
+        .add(product::Column::Id.in_subquery(products))
+        .add(product::Column::Id.in_subquery(parents))
+}
+
+/// Products among `products` and their variants.
+fn self_or_parent_in(products: SelectStatement) -> Condition {
+    Condition::any()
+        .add(product::Column::Id.in_subquery(products.clone()))
+        .add(product::Column::ParentId.in_subquery(products))
+}
+
+fn value_as_bigint() -> Expr {
+    Expr::expr(Expr::cust(VALUE_AS_BIGINT))
+}
+
+fn parse_integer(value: &str) -> Option<i64> {
+    value.trim().parse().ok()
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use entity::file::{self, Entity as File};
+use entity::product::{self, Entity as Product};
+use entity::product_image::{self, Entity as ProductImage};
+use migration::Expr;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseTransaction, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{ProductService, ProductServiceErr};
+
+/// Gallery of a product, the first image is the main one.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductImages {
+    pub(super) product_id: u32,
+    pub(super) photos: Vec<Uuid>,
+}
+
+impl ProductService {
+    /// Adds images to the end of the gallery, files already in it stay where they are.
+    pub async fn attach_images(
+        &self,
+        product_id: u32,
+        files: &[Uuid],
+    ) -> Result<ProductImages, ProductServiceErr> {
+        self.edit_gallery(product_id, |mut photos| {
+            for file in files {
+                if !photos.contains(file) {
+                    photos.push(*file);
+                }
+            }
+
+            Ok(photos)
+        })
+        .await
+    }
+
+    pub async fn detach_image(
+        &self,
+        product_id: u32,
+        file: Uuid,
+    ) -> Result<ProductImages, ProductServiceErr> {
+        self.edit_gallery(product_id, |mut photos| {
+            let position = photos
+                .iter()
+                .position(|photo| *photo == file)
+                .ok_or(ProductServiceErr::NotFound)?;
+
+            photos.remove(position);
+
+            Ok(photos)
+        })
+        .await
+    }
+
+    /// `files` has to list every image of the gallery once, in the new order.
+    pub async fn reorder_images(
+        &self,
+        product_id: u32,
+        files: &[Uuid],
+    ) -> Result<ProductImages, ProductServiceErr> {
+        self.edit_gallery(product_id, |photos| {
+            let current: HashSet<&Uuid> = photos.iter().collect();
+            let requested: HashSet<&Uuid> = files.iter().collect();
+
+            if files.len() != photos.len() || current != requested {
+                return Err(ProductServiceErr::InvalidImageOrder);
+            }
+
+            Ok(files.to_vec())
+        })
+        .await
+    }
+
+    /// Images of the products in gallery order.
+    pub(crate) async fn photos_by_product<C>(
+        db: &C,
+        ids: &[i32],
+    ) -> Result<HashMap<i32, Vec<Uuid>>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut photos: HashMap<i32, Vec<Uuid>> = HashMap::new();
+
+        if ids.is_empty() {
+            return Ok(photos);
+        }
+
+        let images = ProductImage::find()
+            .filter(product_image::Column::ProductId.is_in(ids.to_vec()))
+            .order_by_asc(product_image::Column::ProductId)
+            .order_by_asc(product_image::Column::Position)
+            .all(db)
+            .await?;
+
+        for image in images {
+            photos
+                .entry(image.product_id)
+                .or_default()
+                .push(image.file_id);
+        }
+
+        Ok(photos)
+    }
+
+    /// Makes `file` the main image, `photo` of older clients is the main image.
+    pub(super) async fn set_main_image(
+        transaction: &DatabaseTransaction,
+        product_id: u32,
+        file: Uuid,
+    ) -> Result<(), ProductServiceErr> {
+        Self::check_files(transaction, &[file]).await?;
+
+        let mut photos = Self::photos_by_product(transaction, &[product_id as i32])
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .remove(&(product_id as i32))
+            .unwrap_or_default();
+
+        photos.retain(|photo| *photo != file);
+        photos.insert(0, file);
+
+        Self::write_gallery(transaction, product_id, &photos)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)
+    }
+
+    async fn edit_gallery<F>(
+        &self,
+        product_id: u32,
+        edit: F,
+    ) -> Result<ProductImages, ProductServiceErr>
+    where
+        F: FnOnce(Vec<Uuid>) -> Result<Vec<Uuid>, ProductServiceErr>,
+    {
+        let transaction = self
+            .db
+            .begin()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        // Concurrent edits of the same gallery wait for each other.
+        Product::find_by_id(product_id as i32)
+            .lock_exclusive()
+            .one(&transaction)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .ok_or(ProductServiceErr::NotFound)?;
+
+        let current = Self::photos_by_product(&transaction, &[product_id as i32])
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .remove(&(product_id as i32))
+            .unwrap_or_default();
+
+        let photos = edit(current.clone())?;
+        let added: Vec<Uuid> = photos
+            .iter()
+            .filter(|photo| !current.contains(photo))
+            .copied()
+            .collect();
+
+        Self::check_files(&transaction, &added).await?;
+        Self::write_gallery(&transaction, product_id, &photos)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        Ok(ProductImages { product_id, photos })
+    }
+
+    /// Files that don't exist or were removed can't be shown on a product page.
+    async fn check_files(
+        transaction: &DatabaseTransaction,
+        files: &[Uuid],
+    ) -> Result<(), ProductServiceErr> {
+        if files.is_empty() {
+            return Ok(());
+        }
+
+        let found: HashSet<Uuid> = File::find()
+            .select_only()
+            .column(file::Column::Id)
+            .filter(file::Column::Id.is_in(files.to_vec()))
+            .filter(file::Column::IsRemoved.eq(false))
+            .into_tuple()
+            .all(transaction)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .into_iter()
+            .collect();
+
+        let missing: Vec<Uuid> = files
+            .iter()
+            .filter(|file| !found.contains(file))
+            .copied()
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(ProductServiceErr::FilesNotFound(missing));
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the gallery and keeps `product.photo` pointing to the main image.
+    async fn write_gallery(
+        transaction: &DatabaseTransaction,
+        product_id: u32,
+        photos: &[Uuid],
+    ) -> Result<(), DbErr> {
+        ProductImage::delete_many()
+            .filter(product_image::Column::ProductId.eq(product_id as i32))
+            .exec(transaction)
+            .await?;
+
+        if !photos.is_empty() {
+            ProductImage::insert_many(photos.iter().enumerate().map(|(position, file)| {
+                product_image::ActiveModel {
+                    product_id: Set(product_id as i32),
+                    file_id: Set(*file),
+                    position: Set(position as i32),
+                }
+            }))
+            .exec(transaction)
+            .await?;
+        }
+
+        Product::update_many()
+            .col_expr(product::Column::Photo, Expr::value(photos.first().copied()))
+            .filter(product::Column::Id.eq(product_id as i32))
+            .exec(transaction)
+            .await?;
+
+        Ok(())
+    }
+}
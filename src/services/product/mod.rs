@@ -1,110 +1,204 @@
+pub mod filter;
+pub mod images;
+pub mod search;
+pub mod suggest;
+
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
-use migration::extension::postgres::PgExpr;
-use migration::{Alias, Expr, OnConflict};
+use migration::{Expr, Func, OnConflict};
 use rust_decimal::Decimal;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, JoinType, PaginatorTrait,
-    QueryFilter, QuerySelect, RelationTrait, Select, Set, TransactionTrait,
+    ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, FromQueryResult,
+    JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select, Set,
+    SqlErr, TransactionTrait,
 };
 
 use entity::product::{self, Entity as Product};
 use entity::{category_product, field, field_product};
 use serde::Serialize;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::api::FieldInProductDto;
 use crate::utilities::seaorm_utils::{parse_query_to_model, Prefixer};
 
 use super::field::field_type::FieldType;
+use super::webhook::{webhook_event::WebhookEvent, WebhookService};
+use filter::FieldFilter;
 
+#[derive(Clone)]
 pub struct ProductService {
     db: DatabaseConnection,
+    webhooks: WebhookService,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum ProductServiceErr {
     Internal,
     NotFound,
+    InvalidFieldFilter(u32),
+    InvalidParentId,
+    FilesNotFound(Vec<Uuid>),
+    InvalidImageOrder,
+    OutOfStock(u32),
+    /// Ids of the products whose variants would be left without a parent.
+    HasVariants(Vec<u32>),
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ProductSerializable {
     pub id: i32,
     pub name: String,
     pub price: rust_decimal::Decimal,
     pub article: String,
     pub description: String,
+    /// Main image, the first of `photos`.
     pub photo: Option<Uuid>,
+    pub photos: Vec<Uuid>,
+    pub stock: Option<i32>,
     pub fields: Vec<FieldInProduct>,
+    /// Set when the product is a variant of another one.
+    pub parent_id: Option<i32>,
+    pub variants: Vec<ProductVariantSerializable>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+/// Size, finish or another option of a product, ordered as a product of its own.
+/// Name and description come from the parent, the variant name tells it apart.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductVariantSerializable {
+    pub id: i32,
+    pub name: String,
+    pub price: rust_decimal::Decimal,
+    pub article: String,
+    pub photo: Option<Uuid>,
+    pub photos: Vec<Uuid>,
+    pub stock: Option<i32>,
+    pub fields: Vec<FieldInProduct>,
+}
+
+impl From<ProductSerializable> for ProductVariantSerializable {
+    fn from(value: ProductSerializable) -> Self {
+        ProductVariantSerializable {
+            id: value.id,
+            name: value.name,
+            price: value.price,
+            article: value.article,
+            photo: value.photo,
+            photos: value.photos,
+            stock: value.stock,
+            fields: value.fields,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductsPage {
+    products: Vec<ProductSerializable>,
+    total: u64,
+    page: u64,
+    page_count: u64,
+}
+
+/// Product of an order line. `id` is missing once the product was deleted,
+/// the line still has the name, article and price it was ordered with.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct OrderedProductSerializable {
+    pub id: Option<i32>,
+    pub name: String,
+    pub price: rust_decimal::Decimal,
+    pub article: String,
+    pub description: String,
+    pub photo: Option<Uuid>,
+    pub photos: Vec<Uuid>,
+    pub stock: Option<i32>,
+    pub fields: Vec<FieldInProduct>,
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ProductWithQuantitySerializable {
-    pub product: ProductSerializable,
+    pub product: OrderedProductSerializable,
     pub quantity: u32,
+    pub total: Decimal,
 }
 
 #[derive(Clone, Debug)]
 pub struct ProductWithQuantityModel {
-    pub id: i32,
+    pub id: Option<i32>,
     pub name: String,
     pub price: Decimal,
     pub article: String,
     pub description: String,
     pub photo: Option<Uuid>,
+    pub stock: Option<i32>,
+    pub parent_id: Option<i32>,
     pub quantity: i32,
     pub fields: Vec<FieldInProduct>,
 }
 
 #[derive(Clone, Debug)]
 pub struct ProductWithQuantityWithFieldModel {
-    pub id: i32,
+    pub line_id: i32,
+    pub id: Option<i32>,
     pub name: String,
     pub price: Decimal,
     pub article: String,
     pub description: String,
     pub photo: Option<Uuid>,
+    pub stock: Option<i32>,
+    pub parent_id: Option<i32>,
     pub quantity: i32,
     pub field: Option<FieldWithValue>,
 }
 
 impl From<ProductWithQuantityModel> for ProductWithQuantitySerializable {
     fn from(value: ProductWithQuantityModel) -> Self {
+        let total = value.price * Decimal::from(value.quantity);
+
         ProductWithQuantitySerializable {
-            product: ProductSerializable {
+            product: OrderedProductSerializable {
                 id: value.id,
                 name: value.name,
                 price: value.price,
                 article: value.article,
                 description: value.description,
                 photo: value.photo,
+                photos: Vec::new(),
+                stock: value.stock,
                 fields: value.fields,
+                parent_id: value.parent_id,
             },
             quantity: value.quantity as u32,
+            total,
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ProductIdx {
     idx: Vec<u32>,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ProductInsertionUpdate {
     id: u32,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductStock {
+    id: u32,
+    stock: i32,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ProductAddFieldUpdate {
     product_id: u32,
     field_id: u32,
     value: String,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct FieldInProduct {
     id: u32,
     r#type: FieldType,
@@ -137,6 +231,8 @@ pub struct ProductWithField {
     pub article: String,
     pub description: String,
     pub photo: Option<Uuid>,
+    pub stock: Option<i32>,
+    pub parent_id: Option<i32>,
     pub field: Option<FieldWithValue>,
 }
 
@@ -170,6 +266,8 @@ impl FromQueryResult for ProductWithField {
             article: product.article,
             description: product.description,
             photo: product.photo,
+            stock: product.stock,
+            parent_id: product.parent_id,
             field: field_with_value,
         })
     }
@@ -200,7 +298,11 @@ impl From<product::Model> for ProductSerializable {
             article: model.article,
             description: model.description,
             photo: model.photo,
+            photos: Vec::new(),
+            stock: model.stock,
             fields: Vec::new(),
+            parent_id: model.parent_id,
+            variants: Vec::new(),
         }
     }
 }
@@ -214,7 +316,11 @@ impl From<&product::Model> for ProductSerializable {
             article: model.article.to_owned(),
             description: model.description.to_owned(),
             photo: model.photo,
+            photos: Vec::new(),
+            stock: model.stock,
             fields: Vec::new(),
+            parent_id: model.parent_id,
+            variants: Vec::new(),
         }
     }
 }
@@ -223,7 +329,23 @@ impl ProductService {
     const MAX_PRODUCTS_PER_PAGE: u64 = 15;
 
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        Self {
+            webhooks: WebhookService::new(db.clone()),
+            db,
+        }
+    }
+
+    /// Sends the current state of the product to webhooks once the caller's
+    /// changes are committed.
+    fn dispatch_product(&self, event: WebhookEvent, id: u32) {
+        let service = self.clone();
+
+        actix_web::rt::spawn(async move {
+            match service.get(id).await {
+                Ok(product) => service.webhooks.dispatch(event, &product),
+                Err(err) => log::error!("Product {} webhook skipped: {:?}", id, err),
+            }
+        });
     }
 
     pub fn products_with_field_to_serializable(
@@ -232,7 +354,7 @@ impl ProductService {
         let mut product_index_map: HashMap<u32, usize> = HashMap::new();
         let mut result: Vec<ProductSerializable> = Vec::new();
 
-        for (index, product) in products.iter().enumerate() {
+        for product in products.iter() {
             let product_id = product.id as u32;
 
             if let Entry::Vacant(e) = product_index_map.entry(product_id) {
@@ -243,10 +365,14 @@ impl ProductService {
                     article: product.article.to_owned(),
                     description: product.description.to_owned(),
                     photo: product.photo,
+                    photos: Vec::new(),
+                    stock: product.stock,
                     fields: Vec::new(),
+                    parent_id: product.parent_id,
+                    variants: Vec::new(),
                 });
 
-                e.insert(index);
+                let index = *e.insert(result.len() - 1);
 
                 if let Some(field) = &product.field {
                     result[index].fields.push(field.clone().into());
@@ -259,7 +385,7 @@ impl ProductService {
             }
         }
 
-        return result;
+        result
     }
 
     fn products_selector() -> Select<Product> {
@@ -274,37 +400,157 @@ impl ProductService {
             .selector
     }
 
-    pub async fn search(
+    /// Loads products with their fields, keeping the order of `ids`.
+    async fn products_by_ids(
         &self,
-        text: &str,
-        page: u64,
+        ids: &[i32],
     ) -> Result<Vec<ProductSerializable>, ProductServiceErr> {
-        let model = ProductService::products_selector()
-            .filter(
-                Expr::custom_keyword(Alias::new("\"product\".\"name\"")).ilike(format!("%{text}%")),
-            )
+        let products = ProductService::products_selector()
+            .filter(product::Column::Id.is_in(ids.to_vec()))
             .into_model::<ProductWithField>()
-            .paginate(&self.db, ProductService::MAX_PRODUCTS_PER_PAGE)
-            .fetch_page(page)
+            .all(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        let positions: HashMap<i32, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (*id, position))
+            .collect();
+        let mut products = ProductService::products_with_field_to_serializable(products);
+
+        products.sort_by_key(|product| positions[&product.id]);
+        self.attach_photos(&mut products).await?;
+        self.attach_variants(&mut products).await?;
+
+        Ok(products)
+    }
+
+    async fn attach_photos(
+        &self,
+        products: &mut [ProductSerializable],
+    ) -> Result<(), ProductServiceErr> {
+        let ids: Vec<i32> = products.iter().map(|product| product.id).collect();
+        let mut photos = Self::photos_by_product(&self.db, &ids)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        for product in products.iter_mut() {
+            product.photos = photos.remove(&product.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    async fn attach_variants(
+        &self,
+        products: &mut [ProductSerializable],
+    ) -> Result<(), ProductServiceErr> {
+        let parent_ids: Vec<i32> = products
+            .iter()
+            .filter(|product| product.parent_id.is_none())
+            .map(|product| product.id)
+            .collect();
+
+        if parent_ids.is_empty() {
+            return Ok(());
+        }
+
+        let variants = ProductService::products_selector()
+            .filter(product::Column::ParentId.is_in(parent_ids))
+            .order_by_asc(product::Column::Id)
+            .into_model::<ProductWithField>()
+            .all(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        let positions: HashMap<i32, usize> = products
+            .iter()
+            .enumerate()
+            .map(|(position, product)| (product.id, position))
+            .collect();
+
+        let mut variants = ProductService::products_with_field_to_serializable(variants);
+
+        self.attach_photos(&mut variants).await?;
+
+        for variant in variants {
+            if let Some(position) = variant.parent_id.and_then(|id| positions.get(&id)) {
+                products[*position].variants.push(variant.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn all(
+        &self,
+        filters: &[FieldFilter],
+        page: u64,
+    ) -> Result<ProductsPage, ProductServiceErr> {
+        let condition = self
+            .field_filters_condition(filters)
+            .await?
+            .add(product::Column::ParentId.is_null());
+
+        let total = Product::find()
+            .filter(condition.clone())
+            .count(&self.db)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        // Pick the page of product ids first, otherwise every field of the
+        // joined query would take a slot on the page.
+        let ids: Vec<i32> = Product::find()
+            .filter(condition)
+            .select_only()
+            .column(product::Column::Id)
+            .order_by_asc(product::Column::Id)
+            .offset(page * ProductService::MAX_PRODUCTS_PER_PAGE)
+            .limit(ProductService::MAX_PRODUCTS_PER_PAGE)
+            .into_tuple()
+            .all(&self.db)
             .await
             .map_err(|_| ProductServiceErr::Internal)?;
 
-        Ok(ProductService::products_with_field_to_serializable(model))
+        Ok(ProductsPage {
+            products: self.products_by_ids(&ids).await?,
+            total,
+            page: page + 1,
+            page_count: total.div_ceil(ProductService::MAX_PRODUCTS_PER_PAGE),
+        })
     }
 
-    pub async fn all(&self, page: u64) -> Result<Vec<ProductSerializable>, ProductServiceErr> {
+    pub async fn low_stock(
+        &self,
+        threshold: u32,
+    ) -> Result<Vec<ProductSerializable>, ProductServiceErr> {
         let products = ProductService::products_selector()
+            .filter(product::Column::Stock.lte(threshold as i32))
+            .order_by_asc(product::Column::Stock)
+            .order_by_asc(product::Column::Id)
             .into_model::<ProductWithField>()
-            .paginate(&self.db, ProductService::MAX_PRODUCTS_PER_PAGE)
-            .fetch_page(page)
+            .all(&self.db)
             .await
             .map_err(|_| ProductServiceErr::Internal)?;
 
-        Ok(ProductService::products_with_field_to_serializable(
-            products,
-        ))
+        let mut products = ProductService::products_with_field_to_serializable(products);
+
+        self.attach_photos(&mut products).await?;
+
+        Ok(products)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         &self,
         id: u32,
@@ -314,24 +560,87 @@ impl ProductService {
         description: &str,
         photo: Option<Uuid>,
     ) -> Result<ProductInsertionUpdate, ProductServiceErr> {
+        // `photo` is kept by the gallery, see `set_main_image`, and `stock`
+        // only changes through `adjust_stock` and order reservations.
         let model = product::ActiveModel {
             id: Set(id as i32),
             name: Set(name.to_owned()),
             price: Set(price),
             article: Set(article.to_owned()),
             description: Set(description.to_owned()),
-            photo: Set(photo),
             ..Default::default()
         };
 
+        let transaction = self
+            .db
+            .begin()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
         Product::update(model)
-            .exec(&self.db)
+            .exec(&transaction)
             .await
             .map_err(|err| match err {
                 sea_orm::DbErr::RecordNotFound(_) => ProductServiceErr::NotFound,
                 _ => ProductServiceErr::Internal,
-            })
-            .map(|_| ProductInsertionUpdate { id })
+            })?;
+
+        if let Some(photo) = photo {
+            Self::set_main_image(&transaction, id, photo).await?;
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        self.dispatch_product(WebhookEvent::ProductUpdated, id);
+
+        Ok(ProductInsertionUpdate { id })
+    }
+
+    /// Adds `delta` to the stock in place, so that units reserved by an order
+    /// in the meantime aren't overwritten. Unlimited stock starts counting from zero.
+    pub async fn adjust_stock(
+        &self,
+        id: u32,
+        delta: i32,
+    ) -> Result<ProductStock, ProductServiceErr> {
+        let stock = Expr::expr(Func::coalesce([
+            Expr::col(product::Column::Stock).into(),
+            Expr::val(0).into(),
+        ]))
+        .add(delta);
+
+        let updated = Product::update_many()
+            .col_expr(product::Column::Stock, stock.clone())
+            .filter(product::Column::Id.eq(id as i32))
+            .filter(Expr::expr(stock.clone()).gte(0))
+            .exec_with_returning(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        let Some(product) = updated.into_iter().next() else {
+            let exists = Product::find_by_id(id as i32)
+                .count(&self.db)
+                .await
+                .map_err(|_| ProductServiceErr::Internal)?;
+
+            return Err(match exists {
+                0 => ProductServiceErr::NotFound,
+                _ => ProductServiceErr::OutOfStock(id),
+            });
+        };
+
+        self.dispatch_product(WebhookEvent::ProductUpdated, id);
+
+        Ok(ProductStock {
+            id,
+            stock: product.stock.unwrap_or_default(),
+        })
     }
 
     pub async fn remove_field_from_product(
@@ -384,6 +693,7 @@ impl ProductService {
             })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create(
         &self,
         name: String,
@@ -391,6 +701,7 @@ impl ProductService {
         article: String,
         description: String,
         photo: Option<Uuid>,
+        stock: Option<u32>,
         fields: Vec<FieldInProductDto>,
         category_id: u32,
     ) -> Result<ProductInsertionUpdate, ProductServiceErr> {
@@ -405,7 +716,7 @@ impl ProductService {
             price: Set(price),
             article: Set(article),
             description: Set(description),
-            photo: Set(photo),
+            stock: Set(stock.map(|stock| stock as i32)),
             ..Default::default()
         })
         .exec(&transaction)
@@ -423,23 +734,12 @@ impl ProductService {
         .await
         .map_err(|_| ProductServiceErr::Internal)?;
 
-        if !fields.is_empty() {
-            field_product::Entity::insert_many(fields.iter().map(|f| field_product::ActiveModel {
-                product_id: Set(result.id as i32),
-                field_id: Set(f.id as i32),
-                value: Set(f.value.to_owned()),
-            }))
-            .on_conflict(
-                OnConflict::columns([
-                    field_product::Column::ProductId,
-                    field_product::Column::FieldId,
-                ])
-                .update_column(field_product::Column::Value)
-                .to_owned(),
-            )
-            .exec(&transaction)
+        Self::insert_fields(&transaction, result.id, &fields)
             .await
             .map_err(|_| ProductServiceErr::Internal)?;
+
+        if let Some(photo) = photo {
+            Self::set_main_image(&transaction, result.id, photo).await?;
         }
 
         transaction
@@ -447,49 +747,493 @@ impl ProductService {
             .await
             .map_err(|_| ProductServiceErr::Internal)?;
 
+        self.dispatch_product(WebhookEvent::ProductCreated, result.id);
+
         Ok(result)
     }
 
-    pub async fn get(&self, id: u32) -> Result<ProductSerializable, ProductServiceErr> {
-        let selector = Product::find_by_id(id as i32)
-            .join(JoinType::LeftJoin, product::Relation::FieldProduct.def())
-            .join(JoinType::LeftJoin, field_product::Relation::Field.def());
+    /// Adds a variant to a product. Variants can't have variants of their own.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_variant(
+        &self,
+        parent_id: u32,
+        name: String,
+        price: Decimal,
+        article: String,
+        photo: Option<Uuid>,
+        stock: Option<u32>,
+        fields: Vec<FieldInProductDto>,
+    ) -> Result<ProductInsertionUpdate, ProductServiceErr> {
+        let transaction = self
+            .db
+            .begin()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
 
-        let product = Prefixer::new(selector)
-            .add_columns(field::Entity)
-            .add_columns(field_product::Entity)
-            .add_columns(product::Entity)
-            .selector
-            .into_model::<ProductWithField>()
-            .all(&self.db)
+        let parent = Product::find_by_id(parent_id as i32)
+            .lock_shared()
+            .one(&transaction)
             .await
-            .map_err(|err| {
-                log::error!("{:?}", err);
-                ProductServiceErr::Internal
-            })?;
+            .map_err(|_| ProductServiceErr::Internal)?
+            .ok_or(ProductServiceErr::NotFound)?;
+
+        if parent.parent_id.is_some() {
+            return Err(ProductServiceErr::InvalidParentId);
+        }
+
+        let result = Product::insert(product::ActiveModel {
+            name: Set(name),
+            price: Set(price),
+            article: Set(article),
+            description: Set(String::new()),
+            stock: Set(stock.map(|stock| stock as i32)),
+            parent_id: Set(Some(parent.id)),
+            ..Default::default()
+        })
+        .exec(&transaction)
+        .await
+        .map(|result| ProductInsertionUpdate {
+            id: result.last_insert_id as u32,
+        })
+        .map_err(|_| ProductServiceErr::Internal)?;
+
+        Self::insert_fields(&transaction, result.id, &fields)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        if let Some(photo) = photo {
+            Self::set_main_image(&transaction, result.id, photo).await?;
+        }
 
-        if product.len() == 0 {
-            return Err(ProductServiceErr::NotFound);
+        transaction
+            .commit()
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        self.dispatch_product(WebhookEvent::ProductCreated, result.id);
+
+        Ok(result)
+    }
+
+    async fn insert_fields(
+        transaction: &DatabaseTransaction,
+        product_id: u32,
+        fields: &[FieldInProductDto],
+    ) -> Result<(), DbErr> {
+        if fields.is_empty() {
+            return Ok(());
         }
 
-        let seriallizable = ProductService::products_with_field_to_serializable(product);
+        field_product::Entity::insert_many(fields.iter().map(|f| field_product::ActiveModel {
+            product_id: Set(product_id as i32),
+            field_id: Set(f.id as i32),
+            value: Set(f.value.to_owned()),
+        }))
+        .on_conflict(
+            OnConflict::columns([
+                field_product::Column::ProductId,
+                field_product::Column::FieldId,
+            ])
+            .update_column(field_product::Column::Value)
+            .to_owned(),
+        )
+        .exec(transaction)
+        .await?;
 
-        Ok(seriallizable[0].clone())
+        Ok(())
+    }
+
+    pub async fn get(&self, id: u32) -> Result<ProductSerializable, ProductServiceErr> {
+        self.products_by_ids(&[id as i32])
+            .await?
+            .pop()
+            .ok_or(ProductServiceErr::NotFound)
     }
 
     pub async fn delete(&self, idx: &[u32]) -> Result<ProductIdx, ProductServiceErr> {
-        let values = idx.iter().map(|e| Into::<sea_orm::Value>::into(*e));
+        let values = idx.iter().map(|id| *id as i32);
         let products = Product::find()
             .filter(product::Column::Id.is_in(values.clone()))
             .all(&self.db)
             .await
             .map_err(|_| ProductServiceErr::Internal)?;
 
+        // Variants go away only together with their parent.
+        let mut with_variants: Vec<u32> = Product::find()
+            .select_only()
+            .column(product::Column::ParentId)
+            .distinct()
+            .filter(product::Column::ParentId.is_in(values.clone()))
+            .filter(product::Column::Id.is_not_in(values.clone()))
+            .into_tuple::<i32>()
+            .all(&self.db)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?
+            .into_iter()
+            .map(|id| id as u32)
+            .collect();
+
+        if !with_variants.is_empty() {
+            with_variants.sort();
+
+            return Err(ProductServiceErr::HasVariants(with_variants));
+        }
+
         Product::delete_many()
             .filter(product::Column::Id.is_in(values))
             .exec(&self.db)
             .await
-            .map(|_| products.into())
-            .map_err(|_| ProductServiceErr::Internal)
+            .map_err(|err| match err.sql_err() {
+                // Given a variant after the check above.
+                Some(SqlErr::ForeignKeyConstraintViolation(_)) => {
+                    ProductServiceErr::HasVariants(idx.to_vec())
+                }
+                _ => ProductServiceErr::Internal,
+            })?;
+
+        let deleted: ProductIdx = products.into();
+
+        self.webhooks
+            .dispatch(WebhookEvent::ProductDeleted, &deleted);
+
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use sea_orm::{EntityTrait, Set};
+    use serde_json::json;
+
+    use entity::product::{self, Entity as Product};
+    use entity::{field, field_product, file};
+    use uuid::Uuid;
+
+    use super::{ProductService, ProductServiceErr};
+    use crate::{api::FieldInProductDto, utilities::test_utils::TestDb};
+
+    const FIELDS_PER_PRODUCT: usize = 10;
+
+    /// Products named `{name} {n}`, each with a value for every field.
+    async fn insert_products(
+        service: &ProductService,
+        name: &str,
+        count: usize,
+        fields: &[i32],
+    ) -> Vec<i32> {
+        let mut ids = Vec::with_capacity(count);
+
+        for n in 0..count {
+            let id = Product::insert(product::ActiveModel {
+                name: Set(format!("{name} {n}")),
+                price: Set(dec!(100)),
+                article: Set(format!("{name}-{n}")),
+                description: Set(format!("{name} description")),
+                ..Default::default()
+            })
+            .exec(&service.db)
+            .await
+            .unwrap()
+            .last_insert_id;
+
+            if !fields.is_empty() {
+                field_product::Entity::insert_many(fields.iter().map(|field_id| {
+                    field_product::ActiveModel {
+                        product_id: Set(id),
+                        field_id: Set(*field_id),
+                        value: Set(format!("value {field_id}")),
+                    }
+                }))
+                .exec(&service.db)
+                .await
+                .unwrap();
+            }
+
+            ids.push(id);
+        }
+
+        ids
+    }
+
+    async fn insert_fields(service: &ProductService) -> Vec<i32> {
+        let mut ids = Vec::with_capacity(FIELDS_PER_PRODUCT);
+
+        for n in 0..FIELDS_PER_PRODUCT {
+            let id = field::Entity::insert(field::ActiveModel {
+                name: Set(format!("Field {n}")),
+                r#type: Set(0),
+                ..Default::default()
+            })
+            .exec(&service.db)
+            .await
+            .unwrap()
+            .last_insert_id;
+
+            ids.push(id);
+        }
+
+        ids
+    }
+
+    #[actix_web::test]
+    async fn pages_count_products_not_their_fields() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let fields = insert_fields(&service).await;
+        let ids = insert_products(&service, "Table", 20, &fields).await;
+
+        let first = service.all(&[], 0).await.unwrap();
+        let second = service.all(&[], 1).await.unwrap();
+
+        assert_eq!((first.total, first.page, first.page_count), (20, 1, 2));
+        assert_eq!((second.total, second.page, second.page_count), (20, 2, 2));
+
+        let listed: Vec<i32> = first
+            .products
+            .iter()
+            .chain(second.products.iter())
+            .map(|product| product.id)
+            .collect();
+
+        assert_eq!(first.products.len(), 15);
+        assert_eq!(listed, ids);
+        assert!(first
+            .products
+            .iter()
+            .chain(second.products.iter())
+            .all(|product| product.fields.len() == FIELDS_PER_PRODUCT));
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn search_pages_count_products_not_their_fields() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let fields = insert_fields(&service).await;
+
+        insert_products(&service, "Table", 20, &fields).await;
+        insert_products(&service, "Chair", 5, &fields).await;
+
+        let first = service.search("table", &[], 0).await.unwrap();
+        let second = service.search("table", &[], 1).await.unwrap();
+
+        assert_eq!((first.total, first.page, first.page_count), (20, 1, 2));
+        assert_eq!(first.products.len(), 15);
+        assert_eq!(second.products.len(), 5);
+
+        let mut listed: Vec<i32> = first
+            .products
+            .iter()
+            .chain(second.products.iter())
+            .map(|hit| hit.product.id)
+            .collect();
+
+        listed.sort();
+        listed.dedup();
+
+        assert_eq!(listed.len(), 20);
+        assert!(first
+            .products
+            .iter()
+            .chain(second.products.iter())
+            .all(|hit| hit.product.fields.len() == FIELDS_PER_PRODUCT));
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn lists_variants_under_their_parent() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let fields = insert_fields(&service).await;
+        let table = insert_products(&service, "Table", 1, &[]).await[0];
+
+        for (name, field_id) in [("120 cm", fields[0]), ("160 cm", fields[1])] {
+            service
+                .create_variant(
+                    table as u32,
+                    name.to_owned(),
+                    dec!(150),
+                    format!("table-{name}"),
+                    None,
+                    Some(1),
+                    vec![FieldInProductDto {
+                        id: field_id as u32,
+                        value: name.to_owned(),
+                    }],
+                )
+                .await
+                .unwrap();
+        }
+
+        let page = service.all(&[], 0).await.unwrap();
+
+        assert_eq!(page.total, 1);
+        assert_eq!(page.products[0].id, table);
+
+        let variants: Vec<(&str, usize)> = page.products[0]
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.fields.len()))
+            .collect();
+
+        assert_eq!(variants, vec![("120 cm", 1), ("160 cm", 1)]);
+
+        let variant = page.products[0].variants[0].id as u32;
+        let nested = service
+            .create_variant(
+                variant,
+                "Oak".to_owned(),
+                dec!(150),
+                "table-oak".to_owned(),
+                None,
+                None,
+                Vec::new(),
+            )
+            .await;
+
+        assert!(matches!(nested, Err(ProductServiceErr::InvalidParentId)));
+
+        let alone = service.delete(&[table as u32]).await;
+        assert!(matches!(alone, Err(ProductServiceErr::HasVariants(ids)) if ids == [table as u32]));
+
+        let mut family: Vec<u32> = page.products[0]
+            .variants
+            .iter()
+            .map(|variant| variant.id as u32)
+            .collect();
+        family.push(table as u32);
+
+        service.delete(&family).await.unwrap();
+        assert_eq!(service.all(&[], 0).await.unwrap().total, 0);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn facets_count_a_product_and_its_variants_once() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let fields = insert_fields(&service).await;
+        let table = insert_products(&service, "Table", 1, &fields[..1]).await[0];
+        insert_products(&service, "Chair", 1, &fields[..1]).await;
+
+        for name in ["120 cm", "160 cm"] {
+            service
+                .create_variant(
+                    table as u32,
+                    name.to_owned(),
+                    dec!(150),
+                    format!("table-{name}"),
+                    None,
+                    None,
+                    vec![FieldInProductDto {
+                        id: fields[0] as u32,
+                        value: format!("value {}", fields[0]),
+                    }],
+                )
+                .await
+                .unwrap();
+        }
+
+        let facets = service.facets(None, "").await.unwrap();
+        let facets = serde_json::to_value(facets).unwrap();
+
+        assert_eq!(
+            facets[0]["values"],
+            json!([{ "value": format!("value {}", fields[0]), "count": 2 }])
+        );
+        assert_eq!(facets.as_array().unwrap().len(), 1);
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn gallery_keeps_photo_as_its_first_image() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let product = insert_products(&service, "Lamp", 1, &[]).await[0] as u32;
+        let files: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+
+        file::Entity::insert_many(files.iter().enumerate().map(|(n, id)| file::ActiveModel {
+            id: Set(*id),
+            filename: Set(format!("lamp-{n}.png")),
+            is_removed: Set(n == 3),
+        }))
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+
+        let images = service.attach_images(product, &files[..3]).await.unwrap();
+        assert_eq!(images.photos, files[..3]);
+
+        let images = service
+            .reorder_images(product, &[files[2], files[0], files[1]])
+            .await
+            .unwrap();
+        assert_eq!(images.photos, vec![files[2], files[0], files[1]]);
+
+        let images = service.detach_image(product, files[2]).await.unwrap();
+        assert_eq!(images.photos, files[..2]);
+
+        let found = service.get(product).await.unwrap();
+        assert_eq!(found.photo, Some(files[0]));
+        assert_eq!(found.photos, files[..2]);
+
+        let removed = service.attach_images(product, &files[3..]).await;
+        assert!(matches!(removed, Err(ProductServiceErr::FilesNotFound(_))));
+
+        let partial = service.reorder_images(product, &files[..1]).await;
+        assert!(matches!(partial, Err(ProductServiceErr::InvalidImageOrder)));
+
+        test_db.drop().await;
+    }
+
+    #[actix_web::test]
+    async fn updates_leave_stock_to_adjustments() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = ProductService::new(test_db.db.clone());
+        let lamp = insert_products(&service, "Lamp", 1, &[]).await[0] as u32;
+        let stock = || async {
+            Product::find_by_id(lamp as i32)
+                .one(&test_db.db)
+                .await
+                .unwrap()
+                .unwrap()
+                .stock
+        };
+
+        service.adjust_stock(lamp, 5).await.unwrap();
+        service
+            .update(lamp, "Lamp", dec!(120), "lamp", "Desk lamp", None)
+            .await
+            .unwrap();
+        assert_eq!(stock().await, Some(5));
+
+        service.adjust_stock(lamp, -3).await.unwrap();
+        assert_eq!(stock().await, Some(2));
+
+        let written_off = service.adjust_stock(lamp, -3).await;
+        assert!(matches!(written_off, Err(ProductServiceErr::OutOfStock(id)) if id == lamp));
+        assert_eq!(stock().await, Some(2));
+
+        let missing = service.adjust_stock(9999, 1).await;
+        assert!(matches!(missing, Err(ProductServiceErr::NotFound)));
+
+        test_db.drop().await;
     }
 }
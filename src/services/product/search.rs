@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use entity::product::{self, Entity as Product};
+use migration::{Expr, Query, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, EntityTrait, FromQueryResult, Order, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{
+    filter::{self_or_variant_in, FieldFilter},
+    ProductSerializable, ProductService, ProductServiceErr,
+};
+
+/// `ts_headline` marks matches with these, they are turned into `<mark>` after escaping.
+const MATCH_START: char = '\u{2}';
+const MATCH_STOP: char = '\u{3}';
+
+const NAME_HEADLINE_OPTIONS: &str = "StartSel=\u{2}, StopSel=\u{3}, HighlightAll=true";
+const DESCRIPTION_HEADLINE_OPTIONS: &str = "StartSel=\u{2}, StopSel=\u{3}, \
+    MaxFragments=2, MinWords=8, MaxWords=20, FragmentDelimiter=\" … \"";
+
+/// Matched words wrapped in `<mark>`, the rest of the text is HTML escaped.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductHighlight {
+    name: String,
+    description: String,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductSearchHit {
+    #[serde(flatten)]
+    pub(super) product: ProductSerializable,
+    rank: f32,
+    highlight: ProductHighlight,
+}
+
+/// Search hits of one page, most relevant first.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ProductSearchPage {
+    pub(super) products: Vec<ProductSearchHit>,
+    pub(super) total: u64,
+    pub(super) page: u64,
+    pub(super) page_count: u64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ProductSearchRow {
+    id: i32,
+    search_rank: f32,
+    name_headline: String,
+    description_headline: String,
+}
+
+impl ProductService {
+    /// Full text search over name, article, field values and description,
+    /// most relevant first. Products also match through their variants.
+    pub async fn search(
+        &self,
+        text: &str,
+        filters: &[FieldFilter],
+        page: u64,
+    ) -> Result<ProductSearchPage, ProductServiceErr> {
+        let condition = self
+            .field_filters_condition(filters)
+            .await?
+            .add(product::Column::ParentId.is_null())
+            .add(self_or_variant_in(
+                Query::select()
+                    .column(product::Column::Id)
+                    .from(product::Entity)
+                    .and_where(matches_search(text))
+                    .to_owned(),
+            ));
+
+        let total = Product::find()
+            .filter(condition.clone())
+            .count(&self.db)
+            .await
+            .map_err(|_| ProductServiceErr::Internal)?;
+
+        // Ranked without the field joins, so that a page holds whole products.
+        let rows = Product::find()
+            .select_only()
+            .column(product::Column::Id)
+            .column_as(
+                Expr::cust_with_values(
+                    r#"ts_rank("product"."search_vector", websearch_to_tsquery('russian', $1))"#,
+                    [text],
+                ),
+                "search_rank",
+            )
+            .column_as(
+                headline(r#""product"."name""#, text, NAME_HEADLINE_OPTIONS),
+                "name_headline",
+            )
+            .column_as(
+                headline(
+                    r#""product"."description""#,
+                    text,
+                    DESCRIPTION_HEADLINE_OPTIONS,
+                ),
+                "description_headline",
+            )
+            .filter(condition)
+            .order_by(Expr::cust("search_rank"), Order::Desc)
+            .order_by_asc(product::Column::Id)
+            .offset(page * ProductService::MAX_PRODUCTS_PER_PAGE)
+            .limit(ProductService::MAX_PRODUCTS_PER_PAGE)
+            .into_model::<ProductSearchRow>()
+            .all(&self.db)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        let ids: Vec<i32> = rows.iter().map(|row| row.id).collect();
+        let mut rows: HashMap<i32, ProductSearchRow> =
+            rows.into_iter().map(|row| (row.id, row)).collect();
+
+        // A product deleted in between is left out of the page.
+        let hits = self
+            .products_by_ids(&ids)
+            .await?
+            .into_iter()
+            .filter_map(|product| {
+                let row = rows.remove(&product.id)?;
+
+                Some(ProductSearchHit {
+                    product,
+                    rank: row.search_rank,
+                    highlight: ProductHighlight {
+                        name: mark_matches(&row.name_headline),
+                        description: mark_matches(&row.description_headline),
+                    },
+                })
+            })
+            .collect();
+
+        Ok(ProductSearchPage {
+            products: hits,
+            total,
+            page: page + 1,
+            page_count: total.div_ceil(ProductService::MAX_PRODUCTS_PER_PAGE),
+        })
+    }
+}
+
+/// Products matching the search text, shared by search and facets.
+pub(super) fn matches_search(text: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        r#""product"."search_vector" @@ websearch_to_tsquery('russian', $1)"#,
+        [text],
+    )
+}
+
+fn headline(column: &str, text: &str, options: &str) -> SimpleExpr {
+    let sql = format!(
+        "ts_headline('russian', coalesce({column}, ''), websearch_to_tsquery('russian', $1), $2)"
+    );
+
+    Expr::cust_with_values(sql, [text, options])
+}
+
+fn mark_matches(headline: &str) -> String {
+    let mut result = String::with_capacity(headline.len());
+
+    for c in headline.chars() {
+        match c {
+            MATCH_START => result.push_str("<mark>"),
+            MATCH_STOP => result.push_str("</mark>"),
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
@@ -0,0 +1,285 @@
+use sea_orm::{ConnectionTrait, FromQueryResult, Statement, TransactionTrait, Value as DbValue};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{ProductService, ProductServiceErr};
+use crate::cache::Cache;
+
+/// Keys of the same position in the english and russian layouts.
+const QWERTY: &str = "qwertyuiop[]asdfghjkl;'zxcvbnm,.`";
+const JCUKEN: &str = "йцукенгшщзхъфывапролджэячсмитьбюё";
+
+/// Latin spelling of russian letters, longest first so `shch` wins over `sh`.
+const TRANSLITERATION: [(&str, &str); 33] = [
+    ("shch", "щ"),
+    ("sch", "щ"),
+    ("zh", "ж"),
+    ("kh", "х"),
+    ("ts", "ц"),
+    ("ch", "ч"),
+    ("sh", "ш"),
+    ("yu", "ю"),
+    ("ya", "я"),
+    ("yo", "ё"),
+    ("a", "а"),
+    ("b", "б"),
+    ("v", "в"),
+    ("g", "г"),
+    ("d", "д"),
+    ("e", "е"),
+    ("z", "з"),
+    ("i", "и"),
+    ("j", "й"),
+    ("y", "ы"),
+    ("k", "к"),
+    ("l", "л"),
+    ("m", "м"),
+    ("n", "н"),
+    ("o", "о"),
+    ("p", "п"),
+    ("r", "р"),
+    ("s", "с"),
+    ("t", "т"),
+    ("u", "у"),
+    ("f", "ф"),
+    ("h", "х"),
+    ("c", "ц"),
+];
+
+/// Lower than the default of pg_trgm, so that a typo in a short word still matches.
+const SIMILARITY_THRESHOLD: &str = "0.4";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum SuggestionKind {
+    #[serde(rename = "product")]
+    Product,
+    #[serde(rename = "article")]
+    Article,
+    #[serde(rename = "category")]
+    Category,
+}
+
+/// Product name, article or category name similar to what is typed.
+/// `id` is the id of the product or of the category, a variant's article
+/// leads to its parent, as variants aren't listed on their own.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct Suggestion {
+    kind: SuggestionKind,
+    id: u32,
+    text: String,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct SuggestionRow {
+    kind: String,
+    id: i32,
+    text: String,
+}
+
+impl ProductService {
+    const SUGGESTIONS_TTL_SECONDS: usize = 5 * 60;
+
+    /// Suggestions for a prefix of a search. The text is also tried as typed
+    /// in the other keyboard layout and in transliteration.
+    /// Results are cached for a few minutes, cache failures only skip the cache.
+    pub async fn suggest(
+        &self,
+        cache: &Cache,
+        text: &str,
+        limit: u64,
+    ) -> Result<Vec<Suggestion>, ProductServiceErr> {
+        let text = text.trim().to_lowercase();
+
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let key = format!("suggest:{limit}:{text}");
+
+        if let Ok(Some(cached)) = cache.get_pair(&key) {
+            if let Ok(suggestions) = serde_json::from_str(&cached) {
+                return Ok(suggestions);
+            }
+        }
+
+        let suggestions = self
+            .find_suggestions(&spelling_variants(&text), limit)
+            .await
+            .map_err(|err| {
+                log::error!("{:?}", err);
+                ProductServiceErr::Internal
+            })?;
+
+        if let Ok(value) = serde_json::to_string(&suggestions) {
+            let expire_at = chrono::Utc::now().timestamp() as usize + Self::SUGGESTIONS_TTL_SECONDS;
+
+            if let Err(err) = cache.add_pair(&key, &value, expire_at) {
+                log::error!("Suggestions for {} not cached: {:?}", key, err);
+            }
+        }
+
+        Ok(suggestions)
+    }
+
+    async fn find_suggestions(
+        &self,
+        variants: &[String],
+        limit: u64,
+    ) -> Result<Vec<Suggestion>, sea_orm::DbErr> {
+        let placeholders: Vec<String> = (1..=variants.len()).map(|i| format!("${i}")).collect();
+        let matches = |column: &str| {
+            placeholders
+                .iter()
+                .map(|variant| format!("{variant} <% {column}"))
+                .collect::<Vec<_>>()
+                .join(" OR ")
+        };
+        let score = |column: &str| {
+            let similarities = placeholders
+                .iter()
+                .map(|variant| format!("word_similarity({variant}, {column})"))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("GREATEST({similarities})")
+        };
+
+        let product_name = r#""product"."name""#;
+        let article = r#""product"."article""#;
+        let category_name = r#""category"."name""#;
+        let sql = format!(
+            r#"SELECT "kind", "id", "text" FROM (
+                SELECT 'product' AS "kind", "id", "name" AS "text", {} AS "score"
+                FROM "product" WHERE "parent_id" IS NULL AND ({})
+                UNION ALL
+                SELECT 'article', COALESCE("parent_id", "id"), "article", {}
+                FROM "product" WHERE {}
+                UNION ALL
+                SELECT 'category', "id", "name", {} FROM "category" WHERE {}
+            ) AS "suggestion"
+            ORDER BY "score" DESC, length("text"), "text"
+            LIMIT {limit}"#,
+            score(product_name),
+            matches(product_name),
+            score(article),
+            matches(article),
+            score(category_name),
+            matches(category_name),
+        );
+
+        let transaction = self.db.begin().await?;
+
+        // Only the operators use the threshold, `SET LOCAL` keeps it to this query.
+        transaction
+            .execute_unprepared(&format!(
+                "SET LOCAL pg_trgm.word_similarity_threshold = {SIMILARITY_THRESHOLD}"
+            ))
+            .await?;
+
+        let rows = SuggestionRow::find_by_statement(Statement::from_sql_and_values(
+            transaction.get_database_backend(),
+            sql,
+            variants
+                .iter()
+                .map(|variant| DbValue::from(variant.as_str())),
+        ))
+        .all(&transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Suggestion {
+                kind: match row.kind.as_str() {
+                    "article" => SuggestionKind::Article,
+                    "category" => SuggestionKind::Category,
+                    _ => SuggestionKind::Product,
+                },
+                id: row.id as u32,
+                text: row.text,
+            })
+            .collect())
+    }
+}
+
+/// The text itself, typed in the other layout and transliterated, without repeats.
+fn spelling_variants(text: &str) -> Vec<String> {
+    let mut variants = vec![text.to_owned()];
+
+    for variant in [switch_layout(text), transliterate(text)] {
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+
+    variants
+}
+
+/// `ntktajy` becomes `телефон` and `руддщ` becomes `hello`.
+fn switch_layout(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if let Some(position) = QWERTY.chars().position(|key| key == c) {
+                JCUKEN.chars().nth(position).unwrap_or(c)
+            } else if let Some(position) = JCUKEN.chars().position(|key| key == c) {
+                QWERTY.chars().nth(position).unwrap_or(c)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// `telefon` becomes `телефон`, cyrillic text is spelled in latin instead.
+fn transliterate(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    'outer: while let Some(c) = rest.chars().next() {
+        for (latin, cyrillic) in TRANSLITERATION {
+            if let Some(tail) = rest.strip_prefix(latin) {
+                result.push_str(cyrillic);
+                rest = tail;
+                continue 'outer;
+            }
+
+            if let Some(tail) = rest.strip_prefix(cyrillic) {
+                result.push_str(latin);
+                rest = tail;
+                continue 'outer;
+            }
+        }
+
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spelling_variants, switch_layout, transliterate};
+
+    #[test]
+    fn switches_keyboard_layout_both_ways() {
+        assert_eq!(switch_layout("ntktajy"), "телефон");
+        assert_eq!(switch_layout("руддщ"), "hello");
+        assert_eq!(switch_layout("123"), "123");
+    }
+
+    #[test]
+    fn transliterates_both_ways() {
+        assert_eq!(transliterate("telefon"), "телефон");
+        assert_eq!(transliterate("borshch"), "борщ");
+        assert_eq!(transliterate("шкаф"), "shkaf");
+        assert_eq!(transliterate("tbl-001"), "тбл-001");
+    }
+
+    #[test]
+    fn drops_repeated_variants() {
+        assert_eq!(spelling_variants("123"), vec!["123"]);
+        assert_eq!(spelling_variants("стол").len(), 3);
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use actix_web::http::header::ContentType;
+use chrono::{DateTime, FixedOffset, Utc};
+use entity::webhook;
+use entity::webhook_delivery::{self, Entity as WebhookDelivery};
+use hmac::{Hmac, Mac};
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use utoipa::ToSchema;
+
+use super::webhook_event::WebhookEvent;
+
+pub const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum DeliveryStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "delivered")]
+    Delivered,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+impl From<i32> for DeliveryStatus {
+    fn from(value: i32) -> Self {
+        match value {
+            2 => DeliveryStatus::Delivered,
+            3 => DeliveryStatus::Failed,
+            _ => DeliveryStatus::Pending,
+        }
+    }
+}
+
+impl From<DeliveryStatus> for i32 {
+    fn from(value: DeliveryStatus) -> Self {
+        match value {
+            DeliveryStatus::Pending => 1,
+            DeliveryStatus::Delivered => 2,
+            DeliveryStatus::Failed => 3,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    id: u32,
+    event: WebhookEvent,
+    created_at: DateTime<FixedOffset>,
+    data: &'a serde_json::Value,
+}
+
+/// Hex encoded HMAC-SHA256 of `{timestamp}.{body}`.
+/// Receivers recompute it with their secret and compare with `X-Webhook-Signature`.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Attempt to go on with, a delivery resumed after a restart keeps its count.
+fn first_attempt(delivery: &webhook_delivery::Model) -> u32 {
+    delivery.attempts.max(0) as u32 + 1
+}
+
+/// Pause after a failed `attempt`, doubling with every attempt.
+fn backoff_after(attempt: u32) -> Duration {
+    INITIAL_BACKOFF * 2u32.pow(attempt.clamp(1, MAX_ATTEMPTS) - 1)
+}
+
+/// Posts the delivery until the receiver answers with 2xx, doubling the pause
+/// after every failed attempt. Each attempt is written to the delivery log.
+pub(super) async fn deliver(
+    db: DatabaseConnection,
+    webhook: webhook::Model,
+    delivery: webhook_delivery::Model,
+) {
+    let event: WebhookEvent = delivery.event.into();
+    let body = serde_json::to_vec(&Envelope {
+        id: delivery.id as u32,
+        event,
+        created_at: delivery.created_at,
+        data: &delivery.payload,
+    });
+
+    if let Err(err) = body {
+        log::error!("{:?}", err);
+        return;
+    }
+
+    let body = body.unwrap();
+    let client = awc::Client::builder().timeout(REQUEST_TIMEOUT).finish();
+
+    for attempt in first_attempt(&delivery)..=MAX_ATTEMPTS {
+        let timestamp = Utc::now().timestamp();
+
+        let response = client
+            .post(&webhook.url)
+            .insert_header(ContentType::json())
+            .insert_header(("X-Webhook-Event", event.as_str()))
+            .insert_header(("X-Webhook-Delivery", delivery.id.to_string()))
+            .insert_header(("X-Webhook-Timestamp", timestamp.to_string()))
+            .insert_header((
+                "X-Webhook-Signature",
+                format!("sha256={}", sign(&webhook.secret, timestamp, &body)),
+            ))
+            .send_body(body.clone())
+            .await;
+
+        let (response_status, error) = match response {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16() as i32), None)
+            }
+            Ok(response) => (
+                Some(response.status().as_u16() as i32),
+                Some(format!("Unexpected response status {}", response.status())),
+            ),
+            Err(err) => (None, Some(err.to_string())),
+        };
+
+        let status = match error {
+            None => DeliveryStatus::Delivered,
+            Some(_) if attempt == MAX_ATTEMPTS => DeliveryStatus::Failed,
+            Some(_) => DeliveryStatus::Pending,
+        };
+
+        if let Some(error) = &error {
+            log::warn!(
+                "Webhook delivery {} attempt {} failed: {}",
+                delivery.id,
+                attempt,
+                error
+            );
+        }
+
+        let update = WebhookDelivery::update(webhook_delivery::ActiveModel {
+            id: Set(delivery.id),
+            status: Set(status.into()),
+            attempts: Set(attempt as i32),
+            response_status: Set(response_status),
+            error: Set(error),
+            last_attempt_at: Set(Some(Utc::now().into())),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await;
+
+        if let Err(err) = update {
+            log::error!("{:?}", err);
+        }
+
+        if status != DeliveryStatus::Pending {
+            return;
+        }
+
+        actix_web::rt::time::sleep(backoff_after(attempt)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use entity::webhook_delivery;
+    use serde_json::json;
+
+    use super::{backoff_after, first_attempt, sign, MAX_ATTEMPTS};
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // Same as `hmac.new(b"secret", b'1700000000.{"id":1}', sha256)` in Python.
+        assert_eq!(
+            sign("secret", 1700000000, br#"{"id":1}"#),
+            "3dd1b9aef568d75f6790a84bd2e5dfa1f44409eef3cbdbd3f10b837376100c11"
+        );
+    }
+
+    #[test]
+    fn resumed_delivery_keeps_its_attempts_and_backoff() {
+        let delivery = |attempts| webhook_delivery::Model {
+            id: 1,
+            webhook_id: 1,
+            event: 1,
+            payload: json!({}),
+            status: 1,
+            attempts,
+            response_status: None,
+            error: None,
+            created_at: Utc::now().into(),
+            last_attempt_at: None,
+        };
+
+        assert_eq!(first_attempt(&delivery(0)), 1);
+        assert_eq!(first_attempt(&delivery(2)), 3);
+        assert_eq!(first_attempt(&delivery(-1)), 1);
+
+        let pauses: Vec<u64> = (1..=MAX_ATTEMPTS)
+            .map(|attempt| backoff_after(attempt).as_secs())
+            .collect();
+
+        assert_eq!(pauses, [2, 4, 8, 16, 32]);
+        assert_eq!(backoff_after(MAX_ATTEMPTS + 3), Duration::from_secs(32));
+    }
+}
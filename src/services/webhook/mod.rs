@@ -0,0 +1,346 @@
+pub mod delivery;
+pub mod webhook_event;
+
+use chrono::{DateTime, FixedOffset};
+use entity::webhook::{self, Entity as Webhook};
+use entity::webhook_delivery::{self, Entity as WebhookDelivery};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use delivery::DeliveryStatus;
+use webhook_event::WebhookEvent;
+
+#[derive(Clone)]
+pub struct WebhookService {
+    db: DatabaseConnection,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum WebhookServiceErr {
+    Internal,
+    NotFound,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct WebhookSerializable {
+    id: u32,
+    url: String,
+    event: WebhookEvent,
+    secret: String,
+    active: bool,
+    created_at: DateTime<FixedOffset>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct WebhookDeliverySerializable {
+    id: u32,
+    webhook_id: u32,
+    event: WebhookEvent,
+    payload: serde_json::Value,
+    status: DeliveryStatus,
+    attempts: u32,
+    response_status: Option<u16>,
+    error: Option<String>,
+    created_at: DateTime<FixedOffset>,
+    last_attempt_at: Option<DateTime<FixedOffset>>,
+}
+
+impl From<webhook::Model> for WebhookSerializable {
+    fn from(value: webhook::Model) -> Self {
+        Self {
+            id: value.id as u32,
+            url: value.url,
+            event: value.event.into(),
+            secret: value.secret,
+            active: value.active,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<webhook_delivery::Model> for WebhookDeliverySerializable {
+    fn from(value: webhook_delivery::Model) -> Self {
+        Self {
+            id: value.id as u32,
+            webhook_id: value.webhook_id as u32,
+            event: value.event.into(),
+            payload: value.payload,
+            status: value.status.into(),
+            attempts: value.attempts as u32,
+            response_status: value.response_status.map(|status| status as u16),
+            error: value.error,
+            created_at: value.created_at,
+            last_attempt_at: value.last_attempt_at,
+        }
+    }
+}
+
+impl WebhookService {
+    pub const DELIVERIES_PER_PAGE: u64 = 50;
+
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn all(&self) -> Result<Vec<WebhookSerializable>, WebhookServiceErr> {
+        Webhook::find()
+            .order_by_asc(webhook::Column::Id)
+            .all(&self.db)
+            .await
+            .map(|webhooks| webhooks.into_iter().map(Into::into).collect())
+            .map_err(|_| WebhookServiceErr::Internal)
+    }
+
+    /// A random secret is generated when none is given.
+    pub async fn create(
+        &self,
+        url: &str,
+        event: WebhookEvent,
+        secret: Option<String>,
+    ) -> Result<WebhookSerializable, WebhookServiceErr> {
+        let model = webhook::ActiveModel {
+            url: Set(url.to_owned()),
+            event: Set(event.into()),
+            secret: Set(secret.unwrap_or_else(|| Uuid::new_v4().simple().to_string())),
+            ..Default::default()
+        };
+
+        model.insert(&self.db).await.map(Into::into).map_err(|err| {
+            log::error!("{:?}", err);
+            WebhookServiceErr::Internal
+        })
+    }
+
+    pub async fn update(
+        &self,
+        id: u32,
+        url: Option<&str>,
+        event: Option<WebhookEvent>,
+        active: Option<bool>,
+    ) -> Result<WebhookSerializable, WebhookServiceErr> {
+        let webhook = Webhook::find_by_id(id as i32)
+            .one(&self.db)
+            .await
+            .map_err(|_| WebhookServiceErr::Internal)?
+            .ok_or(WebhookServiceErr::NotFound)?;
+
+        let mut webhook: webhook::ActiveModel = webhook.into();
+
+        if let Some(url) = url {
+            webhook.url = Set(url.to_owned());
+        }
+
+        if let Some(event) = event {
+            webhook.event = Set(event.into());
+        }
+
+        if let Some(active) = active {
+            webhook.active = Set(active);
+        }
+
+        webhook
+            .update(&self.db)
+            .await
+            .map(Into::into)
+            .map_err(|_| WebhookServiceErr::Internal)
+    }
+
+    pub async fn delete(&self, id: u32) -> Result<WebhookSerializable, WebhookServiceErr> {
+        let webhook = Webhook::find_by_id(id as i32)
+            .one(&self.db)
+            .await
+            .map_err(|_| WebhookServiceErr::Internal)?
+            .ok_or(WebhookServiceErr::NotFound)?;
+
+        Webhook::delete_by_id(id as i32)
+            .exec(&self.db)
+            .await
+            .map(|_| webhook.into())
+            .map_err(|_| WebhookServiceErr::Internal)
+    }
+
+    pub async fn deliveries(
+        &self,
+        webhook_id: u32,
+        page: u64,
+    ) -> Result<Vec<WebhookDeliverySerializable>, WebhookServiceErr> {
+        Webhook::find_by_id(webhook_id as i32)
+            .one(&self.db)
+            .await
+            .map_err(|_| WebhookServiceErr::Internal)?
+            .ok_or(WebhookServiceErr::NotFound)?;
+
+        WebhookDelivery::find()
+            .filter(webhook_delivery::Column::WebhookId.eq(webhook_id as i32))
+            .order_by_desc(webhook_delivery::Column::Id)
+            .offset(page * Self::DELIVERIES_PER_PAGE)
+            .limit(Self::DELIVERIES_PER_PAGE)
+            .all(&self.db)
+            .await
+            .map(|deliveries| deliveries.into_iter().map(Into::into).collect())
+            .map_err(|_| WebhookServiceErr::Internal)
+    }
+
+    /// Sends the payload of an existing delivery again as a new delivery,
+    /// so the log keeps both attempts.
+    pub async fn replay(
+        &self,
+        delivery_id: u32,
+    ) -> Result<WebhookDeliverySerializable, WebhookServiceErr> {
+        let (delivery, webhook) = WebhookDelivery::find_by_id(delivery_id as i32)
+            .find_also_related(Webhook)
+            .one(&self.db)
+            .await
+            .map_err(|_| WebhookServiceErr::Internal)?
+            .ok_or(WebhookServiceErr::NotFound)?;
+
+        let webhook = webhook.ok_or(WebhookServiceErr::NotFound)?;
+
+        let replayed = webhook_delivery::ActiveModel {
+            webhook_id: Set(delivery.webhook_id),
+            event: Set(delivery.event),
+            payload: Set(delivery.payload),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+        .map_err(|_| WebhookServiceErr::Internal)?;
+
+        self.spawn_delivery(webhook, replayed.clone());
+
+        Ok(replayed.into())
+    }
+
+    /// Queues delivery of `data` to every active webhook subscribed to `event`.
+    /// Returns immediately, failures only end up in the log.
+    pub fn dispatch<T>(&self, event: WebhookEvent, data: &T)
+    where
+        T: Serialize,
+    {
+        let payload = serde_json::to_value(data);
+
+        if let Err(err) = payload {
+            log::error!("{:?}", err);
+            return;
+        }
+
+        let service = self.clone();
+        let payload = payload.unwrap();
+
+        actix_web::rt::spawn(async move {
+            if let Err(err) = service.enqueue(event, payload).await {
+                log::error!("Webhook dispatch of {} failed: {:?}", event.as_str(), err);
+            }
+        });
+    }
+
+    /// Picks up deliveries that were still pending when the server stopped.
+    pub async fn resume_pending(&self) -> Result<(), WebhookServiceErr> {
+        let pending = WebhookDelivery::find()
+            .find_also_related(Webhook)
+            .filter(webhook_delivery::Column::Status.eq(i32::from(DeliveryStatus::Pending)))
+            .all(&self.db)
+            .await
+            .map_err(|_| WebhookServiceErr::Internal)?;
+
+        for (delivery, webhook) in pending {
+            if let Some(webhook) = webhook {
+                self.spawn_delivery(webhook, delivery);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn enqueue(
+        &self,
+        event: WebhookEvent,
+        payload: serde_json::Value,
+    ) -> Result<(), sea_orm::DbErr> {
+        let webhooks = Webhook::find()
+            .filter(webhook::Column::Event.eq(i32::from(event)))
+            .filter(webhook::Column::Active.eq(true))
+            .all(&self.db)
+            .await?;
+
+        for webhook in webhooks {
+            let delivery = webhook_delivery::ActiveModel {
+                webhook_id: Set(webhook.id),
+                event: Set(event.into()),
+                payload: Set(payload.clone()),
+                ..Default::default()
+            }
+            .insert(&self.db)
+            .await?;
+
+            self.spawn_delivery(webhook, delivery);
+        }
+
+        Ok(())
+    }
+
+    fn spawn_delivery(&self, webhook: webhook::Model, delivery: webhook_delivery::Model) {
+        actix_web::rt::spawn(delivery::deliver(self.db.clone(), webhook, delivery));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder, Set};
+    use serde_json::json;
+
+    use entity::webhook_delivery::{self, Entity as WebhookDelivery};
+
+    use super::{DeliveryStatus, WebhookEvent, WebhookService, WebhookServiceErr};
+    use crate::utilities::test_utils::TestDb;
+
+    #[actix_web::test]
+    async fn replay_logs_a_new_pending_delivery() {
+        let Some(test_db) = TestDb::new().await else {
+            return;
+        };
+        let service = WebhookService::new(test_db.db.clone());
+        // Nothing listens there, the replayed delivery only gets logged.
+        let webhook = service
+            .create("http://127.0.0.1:1/hook", WebhookEvent::OrderCreated, None)
+            .await
+            .unwrap();
+        let failed = webhook_delivery::ActiveModel {
+            webhook_id: Set(webhook.id as i32),
+            event: Set(WebhookEvent::OrderCreated.into()),
+            payload: Set(json!({ "id": 7 })),
+            status: Set(DeliveryStatus::Failed.into()),
+            attempts: Set(5),
+            ..Default::default()
+        }
+        .insert(&test_db.db)
+        .await
+        .unwrap();
+
+        let replayed = service.replay(failed.id as u32).await.unwrap();
+
+        assert_ne!(replayed.id, failed.id as u32);
+        assert_eq!(replayed.payload, json!({ "id": 7 }));
+        assert_eq!(replayed.status, DeliveryStatus::Pending);
+        assert_eq!(replayed.attempts, 0);
+
+        let log = WebhookDelivery::find()
+            .order_by_asc(webhook_delivery::Column::Id)
+            .all(&test_db.db)
+            .await
+            .unwrap();
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0], failed);
+
+        let missing = service.replay(9999).await;
+        assert!(matches!(missing, Err(WebhookServiceErr::NotFound)));
+
+        test_db.drop().await;
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "order.created")]
+    OrderCreated,
+    #[serde(rename = "order.status_changed")]
+    OrderStatusChanged,
+    #[serde(rename = "product.created")]
+    ProductCreated,
+    #[serde(rename = "product.updated")]
+    ProductUpdated,
+    #[serde(rename = "product.deleted")]
+    ProductDeleted,
+    #[serde(rename = "category.created")]
+    CategoryCreated,
+    #[serde(rename = "category.updated")]
+    CategoryUpdated,
+    #[serde(rename = "category.deleted")]
+    CategoryDeleted,
+}
+
+impl WebhookEvent {
+    /// Name sent in the `X-Webhook-Event` header, same as the serialized value.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::OrderCreated => "order.created",
+            WebhookEvent::OrderStatusChanged => "order.status_changed",
+            WebhookEvent::ProductCreated => "product.created",
+            WebhookEvent::ProductUpdated => "product.updated",
+            WebhookEvent::ProductDeleted => "product.deleted",
+            WebhookEvent::CategoryCreated => "category.created",
+            WebhookEvent::CategoryUpdated => "category.updated",
+            WebhookEvent::CategoryDeleted => "category.deleted",
+        }
+    }
+}
+
+impl From<i32> for WebhookEvent {
+    fn from(value: i32) -> Self {
+        match value {
+            2 => WebhookEvent::OrderStatusChanged,
+            3 => WebhookEvent::ProductCreated,
+            4 => WebhookEvent::ProductUpdated,
+            5 => WebhookEvent::ProductDeleted,
+            6 => WebhookEvent::CategoryCreated,
+            7 => WebhookEvent::CategoryUpdated,
+            8 => WebhookEvent::CategoryDeleted,
+            _ => WebhookEvent::OrderCreated,
+        }
+    }
+}
+
+impl From<WebhookEvent> for i32 {
+    fn from(value: WebhookEvent) -> Self {
+        match value {
+            WebhookEvent::OrderCreated => 1,
+            WebhookEvent::OrderStatusChanged => 2,
+            WebhookEvent::ProductCreated => 3,
+            WebhookEvent::ProductUpdated => 4,
+            WebhookEvent::ProductDeleted => 5,
+            WebhookEvent::CategoryCreated => 6,
+            WebhookEvent::CategoryUpdated => 7,
+            WebhookEvent::CategoryDeleted => 8,
+        }
+    }
+}
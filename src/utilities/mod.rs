@@ -1,2 +1,4 @@
 pub mod seaorm_utils;
 pub mod serde_utils;
+#[cfg(test)]
+pub mod test_utils;